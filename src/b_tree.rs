@@ -0,0 +1,518 @@
+use std::cmp::Ordering;
+
+use crate::dfs_on_bst::SearchTree;
+
+#[derive(Debug)]
+struct Node<T> {
+    keys: Vec<T>,
+    // empty for leaves, otherwise always `keys.len() + 1` children
+    children: Vec<Node<T>>,
+}
+
+impl<T> Node<T> {
+    fn leaf() -> Self {
+        Node {
+            keys: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+/// B-tree where every node except the root holds between
+/// `min_degree - 1` and `2 * min_degree - 1` keys
+#[derive(Debug)]
+pub struct BTree<T> {
+    min_degree: usize,
+    length: usize,
+    root: Node<T>,
+}
+
+impl<T: Ord> BTree<T> {
+    pub fn new(min_degree: usize) -> Self {
+        assert!(min_degree >= 2, "minimum degree must be at least 2");
+
+        BTree {
+            min_degree,
+            length: 0,
+            root: Node::leaf(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn min_degree(&self) -> usize {
+        self.min_degree
+    }
+
+    /// number of levels, all leaves sit at this depth
+    pub fn height(&self) -> usize {
+        let mut height = 1;
+        let mut node = &self.root;
+
+        while let Some(child) = node.children.first() {
+            height += 1;
+            node = child;
+        }
+
+        height
+    }
+
+    pub fn contains(&self, needle: &T) -> bool {
+        let mut node = &self.root;
+
+        loop {
+            match node.keys.binary_search(needle) {
+                Ok(_) => return true,
+                Err(_) if node.is_leaf() => return false,
+                Err(idx) => node = &node.children[idx],
+            }
+        }
+    }
+
+    /// inserts a value, ignoring duplicates like the BST does
+    /// returns true if the value was not already present
+    pub fn insert(&mut self, value: T) -> bool {
+        let max_keys = 2 * self.min_degree - 1;
+
+        // split a full root up front, this is the only way the tree grows taller
+        if self.root.keys.len() == max_keys {
+            let old_root = std::mem::replace(&mut self.root, Node::leaf());
+            self.root.children.push(old_root);
+            Self::split_child(&mut self.root, 0, self.min_degree);
+        }
+
+        let inserted = Self::insert_non_full(&mut self.root, value, self.min_degree);
+
+        if inserted {
+            self.length += 1;
+        }
+
+        inserted
+    }
+
+    /// moves the upper half of the full child at `idx` into a new right sibling
+    /// and lifts the median key into the parent
+    fn split_child(parent: &mut Node<T>, idx: usize, min_degree: usize) {
+        let child = &mut parent.children[idx];
+
+        let right = Node {
+            keys: child.keys.split_off(min_degree),
+            children: if child.is_leaf() {
+                Vec::new()
+            } else {
+                child.children.split_off(min_degree)
+            },
+        };
+        let median = child.keys.pop().unwrap();
+
+        parent.keys.insert(idx, median);
+        parent.children.insert(idx + 1, right);
+    }
+
+    fn insert_non_full(node: &mut Node<T>, value: T, min_degree: usize) -> bool {
+        let mut idx = match node.keys.binary_search(&value) {
+            Ok(_) => return false,
+            Err(idx) => idx,
+        };
+
+        if node.is_leaf() {
+            node.keys.insert(idx, value);
+            return true;
+        }
+
+        if node.children[idx].keys.len() == 2 * min_degree - 1 {
+            Self::split_child(node, idx, min_degree);
+
+            match value.cmp(&node.keys[idx]) {
+                Ordering::Equal => return false,
+                Ordering::Greater => idx += 1,
+                Ordering::Less => {}
+            }
+        }
+
+        Self::insert_non_full(&mut node.children[idx], value, min_degree)
+    }
+
+    /// deletes a value from the tree
+    /// returns true if the value was found and deleted
+    pub fn delete(&mut self, value: &T) -> bool {
+        let deleted = Self::delete_from(&mut self.root, value, self.min_degree);
+
+        // an emptied internal root hands over to its only child
+        if self.root.keys.is_empty() && !self.root.is_leaf() {
+            self.root = self.root.children.pop().unwrap();
+        }
+
+        if deleted {
+            self.length -= 1;
+        }
+
+        deleted
+    }
+
+    /// every child we descend into is first topped up to at least `min_degree` keys,
+    /// so removing a key from it can never leave it underfull
+    fn delete_from(node: &mut Node<T>, value: &T, min_degree: usize) -> bool {
+        match node.keys.binary_search(value) {
+            Ok(idx) if node.is_leaf() => {
+                node.keys.remove(idx);
+                true
+            }
+            Ok(idx) => {
+                if node.children[idx].keys.len() >= min_degree {
+                    // replace with the in-order predecessor
+                    node.keys[idx] = Self::remove_max(&mut node.children[idx], min_degree);
+                } else if node.children[idx + 1].keys.len() >= min_degree {
+                    // replace with the in-order successor
+                    node.keys[idx] = Self::remove_min(&mut node.children[idx + 1], min_degree);
+                } else {
+                    // both neighbours are minimal, pull the key down into a merged child
+                    Self::merge_children(node, idx);
+                    return Self::delete_from(&mut node.children[idx], value, min_degree);
+                }
+
+                true
+            }
+            Err(_) if node.is_leaf() => false,
+            Err(idx) => {
+                let idx = Self::fill_child(node, idx, min_degree);
+                Self::delete_from(&mut node.children[idx], value, min_degree)
+            }
+        }
+    }
+
+    fn remove_max(node: &mut Node<T>, min_degree: usize) -> T {
+        if node.is_leaf() {
+            return node.keys.pop().unwrap();
+        }
+
+        let idx = Self::fill_child(node, node.children.len() - 1, min_degree);
+        Self::remove_max(&mut node.children[idx], min_degree)
+    }
+
+    fn remove_min(node: &mut Node<T>, min_degree: usize) -> T {
+        if node.is_leaf() {
+            return node.keys.remove(0);
+        }
+
+        let idx = Self::fill_child(node, 0, min_degree);
+        Self::remove_min(&mut node.children[idx], min_degree)
+    }
+
+    /// makes sure the child at `idx` has at least `min_degree` keys,
+    /// borrowing from a sibling when one can spare a key or merging otherwise
+    /// returns the index the child ended up at
+    fn fill_child(node: &mut Node<T>, idx: usize, min_degree: usize) -> usize {
+        if node.children[idx].keys.len() >= min_degree {
+            return idx;
+        }
+
+        let last = node.children.len() - 1;
+
+        if idx > 0 && node.children[idx - 1].keys.len() >= min_degree {
+            Self::borrow_from_prev(node, idx);
+            idx
+        } else if idx < last && node.children[idx + 1].keys.len() >= min_degree {
+            Self::borrow_from_next(node, idx);
+            idx
+        } else if idx < last {
+            Self::merge_children(node, idx);
+            idx
+        } else {
+            Self::merge_children(node, idx - 1);
+            idx - 1
+        }
+    }
+
+    /// rotates the last key of the left sibling through the parent into the child
+    fn borrow_from_prev(node: &mut Node<T>, idx: usize) {
+        let (left, right) = node.children.split_at_mut(idx);
+        let sibling = &mut left[idx - 1];
+        let child = &mut right[0];
+
+        let lifted = sibling.keys.pop().unwrap();
+        let lowered = std::mem::replace(&mut node.keys[idx - 1], lifted);
+        child.keys.insert(0, lowered);
+
+        if let Some(grandchild) = sibling.children.pop() {
+            child.children.insert(0, grandchild);
+        }
+    }
+
+    /// rotates the first key of the right sibling through the parent into the child
+    fn borrow_from_next(node: &mut Node<T>, idx: usize) {
+        let (left, right) = node.children.split_at_mut(idx + 1);
+        let child = &mut left[idx];
+        let sibling = &mut right[0];
+
+        let lifted = sibling.keys.remove(0);
+        let lowered = std::mem::replace(&mut node.keys[idx], lifted);
+        child.keys.push(lowered);
+
+        if !sibling.is_leaf() {
+            child.children.push(sibling.children.remove(0));
+        }
+    }
+
+    /// folds the child at `idx + 1` and the separating key into the child at `idx`
+    fn merge_children(node: &mut Node<T>, idx: usize) {
+        let right = node.children.remove(idx + 1);
+        let separator = node.keys.remove(idx);
+        let child = &mut node.children[idx];
+
+        child.keys.push(separator);
+        child.keys.extend(right.keys);
+        child.children.extend(right.children);
+    }
+
+    pub fn iter(&self) -> BTreeIter<'_, T> {
+        let mut iter = BTreeIter { stack: Vec::new() };
+        iter.push_left(&self.root);
+        iter
+    }
+
+    /// checks key ordering, node fill factors, child counts and uniform leaf depth
+    pub fn is_valid(&self) -> bool {
+        let mut leaf_depth = None;
+
+        self.root.keys.len() < 2 * self.min_degree
+            && (self.is_empty() || !self.root.keys.is_empty())
+            && self.iter().count() == self.length
+            && self.is_valid_node(&self.root, None, None, 0, &mut leaf_depth)
+    }
+
+    fn is_valid_node(
+        &self,
+        node: &Node<T>,
+        lower: Option<&T>,
+        upper: Option<&T>,
+        depth: usize,
+        leaf_depth: &mut Option<usize>,
+    ) -> bool {
+        let is_root = depth == 0;
+        let keys = node.keys.len();
+
+        if !is_root && (keys < self.min_degree - 1 || keys > 2 * self.min_degree - 1) {
+            return false;
+        }
+
+        if node.keys.windows(2).any(|w| w[0] >= w[1]) {
+            return false;
+        }
+
+        if lower.is_some_and(|lo| node.keys.first().is_some_and(|k| k <= lo))
+            || upper.is_some_and(|hi| node.keys.last().is_some_and(|k| k >= hi))
+        {
+            return false;
+        }
+
+        if node.is_leaf() {
+            return *leaf_depth.get_or_insert(depth) == depth;
+        }
+
+        if node.children.len() != keys + 1 {
+            return false;
+        }
+
+        node.children.iter().enumerate().all(|(i, child)| {
+            let lo = if i == 0 { lower } else { node.keys.get(i - 1) };
+            let hi = if i == keys { upper } else { node.keys.get(i) };
+            self.is_valid_node(child, lo, hi, depth + 1, leaf_depth)
+        })
+    }
+}
+
+/// in-order iterator over a `BTree`
+pub struct BTreeIter<'a, T> {
+    // each entry is a node and the index of the next key to yield from it
+    stack: Vec<(&'a Node<T>, usize)>,
+}
+
+impl<'a, T> BTreeIter<'a, T> {
+    fn push_left(&mut self, mut node: &'a Node<T>) {
+        loop {
+            self.stack.push((node, 0));
+
+            match node.children.first() {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+    }
+}
+
+impl<'a, T> Iterator for BTreeIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, idx)) = self.stack.pop() {
+            if idx < node.keys.len() {
+                self.stack.push((node, idx + 1));
+
+                if let Some(child) = node.children.get(idx + 1) {
+                    self.push_left(child);
+                }
+
+                return Some(&node.keys[idx]);
+            }
+        }
+
+        None
+    }
+}
+
+impl SearchTree for BTree<i32> {
+    fn contains(&self, value: i32) -> bool {
+        BTree::contains(self, &value)
+    }
+
+    fn insert(&mut self, value: i32) {
+        BTree::insert(self, value);
+    }
+
+    fn delete(&mut self, value: i32) -> bool {
+        BTree::delete(self, &value)
+    }
+
+    fn in_order(&self) -> Vec<i32> {
+        self.iter().copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfs_on_bst::search_tree_tests;
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_shared_search_tree_suites() {
+        for min_degree in 2..=5 {
+            search_tree_tests::check_insert_and_contains(BTree::new(min_degree));
+            search_tree_tests::check_delete(BTree::new(min_degree));
+            search_tree_tests::check_random_operations(BTree::new(min_degree), 28);
+        }
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let tree: BTree<i32> = BTree::new(3);
+
+        assert!(tree.is_empty());
+        assert_eq!(tree.min_degree(), 3);
+        assert!(!tree.contains(&1));
+        assert_eq!(tree.iter().next(), None);
+        assert_eq!(tree.height(), 1);
+        assert!(tree.is_valid());
+    }
+
+    #[test]
+    fn test_insert_keeps_invariants() {
+        for min_degree in 2..=6 {
+            let mut tree = BTree::new(min_degree);
+
+            for value in 0..500 {
+                assert!(tree.insert(value));
+                assert!(tree.is_valid(), "invalid after inserting {value}");
+            }
+
+            assert!(!tree.insert(250));
+            assert_eq!(tree.len(), 500);
+            assert_eq!(
+                tree.iter().copied().collect::<Vec<_>>(),
+                (0..500).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_root_split_grows_height() {
+        let mut tree = BTree::new(2);
+
+        // min degree 2 holds at most 3 keys per node
+        tree.insert(1);
+        tree.insert(2);
+        tree.insert(3);
+        assert_eq!(tree.height(), 1);
+
+        tree.insert(4);
+        assert_eq!(tree.height(), 2);
+        assert!(tree.is_valid());
+    }
+
+    #[test]
+    fn test_delete_keeps_invariants() {
+        let mut rng = Rng::new(128);
+
+        for min_degree in 2..=5 {
+            let mut values: Vec<i32> = (0..300).collect();
+            rng.shuffle(&mut values);
+
+            let mut tree = BTree::new(min_degree);
+            for value in &values {
+                tree.insert(*value);
+            }
+
+            rng.shuffle(&mut values);
+
+            for (deleted, value) in values.iter().enumerate() {
+                assert!(tree.delete(value));
+                assert!(!tree.contains(value));
+                assert!(tree.is_valid(), "invalid after deleting {value}");
+                assert_eq!(tree.len(), values.len() - deleted - 1);
+            }
+
+            assert!(tree.is_empty());
+            assert_eq!(tree.height(), 1);
+        }
+    }
+
+    #[test]
+    fn test_delete_shrinks_height() {
+        let mut tree = BTree::new(2);
+
+        for value in 0..100 {
+            tree.insert(value);
+        }
+
+        let tall = tree.height();
+
+        for value in 0..95 {
+            tree.delete(&value);
+        }
+
+        assert!(tree.height() < tall);
+        assert!(tree.is_valid());
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<_>>(),
+            vec![95, 96, 97, 98, 99]
+        );
+    }
+
+    #[test]
+    fn test_generic_keys() {
+        let mut tree = BTree::new(2);
+
+        for word in ["pear", "apple", "fig", "kiwi", "banana", "cherry"] {
+            tree.insert(word.to_string());
+        }
+
+        assert!(tree.contains(&"fig".to_string()));
+        assert!(tree.delete(&"apple".to_string()));
+        assert_eq!(
+            tree.iter().map(String::as_str).collect::<Vec<_>>(),
+            vec!["banana", "cherry", "fig", "kiwi", "pear"]
+        );
+        assert!(tree.is_valid());
+    }
+}
@@ -0,0 +1,270 @@
+use crate::binary_node::BinaryNode;
+
+impl BinaryNode {
+    /// builds a height-balanced BST from sorted values in O(n)
+    /// duplicates are dropped, matching `insert` which ignores equal values
+    pub fn from_sorted(values: &[i32]) -> Option<Box<BinaryNode>> {
+        Self::from_sorted_iter(values.iter().copied())
+    }
+
+    /// builds a height-balanced BST from an iterator yielding values in ascending order
+    ///
+    /// panics if a value is smaller than the one before it
+    pub fn from_sorted_iter<I: IntoIterator<Item = i32>>(values: I) -> Option<Box<BinaryNode>> {
+        let mut sorted: Vec<i32> = Vec::new();
+
+        for value in values {
+            assert!(
+                sorted.last().is_none_or(|last| *last <= value),
+                "values must be sorted in ascending order"
+            );
+
+            if sorted.last() != Some(&value) {
+                sorted.push(value);
+            }
+        }
+
+        let mut iter = sorted.into_iter();
+        let len = iter.len();
+        Self::build(&mut iter, len)
+    }
+
+    /// consumes exactly `len` values from the iterator in in-order position:
+    /// left subtree first, then the root, then the right subtree
+    fn build(values: &mut impl Iterator<Item = i32>, len: usize) -> Option<Box<BinaryNode>> {
+        if len == 0 {
+            return None;
+        }
+
+        let left_len = len / 2;
+        let left = Self::build(values, left_len);
+        let value = values.next()?;
+        let right = Self::build(values, len - left_len - 1);
+
+        Some(Box::new(BinaryNode::with_children(value, left, right)))
+    }
+
+    /// merges two BSTs into a single balanced BST in O(n + m)
+    /// by walking both in-order and merging the two sorted sequences
+    pub fn merge(a: Option<&BinaryNode>, b: Option<&BinaryNode>) -> Option<Box<BinaryNode>> {
+        let left = Self::sorted_values(a);
+        let right = Self::sorted_values(b);
+
+        let mut merged = Vec::with_capacity(left.len() + right.len());
+        let (mut i, mut j) = (0, 0);
+
+        while i < left.len() && j < right.len() {
+            if left[i] <= right[j] {
+                merged.push(left[i]);
+                i += 1;
+            } else {
+                merged.push(right[j]);
+                j += 1;
+            }
+        }
+
+        merged.extend_from_slice(&left[i..]);
+        merged.extend_from_slice(&right[j..]);
+
+        Self::from_sorted(&merged)
+    }
+
+    /// rebuilds a (possibly degenerate) tree into a height-balanced one
+    pub fn rebalance(node: &mut Option<Box<BinaryNode>>) {
+        let values = Self::into_sorted_values(node.take());
+        *node = Self::from_sorted(&values);
+    }
+
+    /// iterative in-order walk over a borrowed tree
+    fn sorted_values(mut curr: Option<&BinaryNode>) -> Vec<i32> {
+        let mut values = Vec::new();
+        let mut stack: Vec<&BinaryNode> = Vec::new();
+
+        loop {
+            while let Some(node) = curr {
+                stack.push(node);
+                curr = node.left.as_deref();
+            }
+
+            let Some(node) = stack.pop() else {
+                break;
+            };
+
+            values.push(node.value);
+            curr = node.right.as_deref();
+        }
+
+        values
+    }
+
+    /// iterative in-order walk that takes the tree apart as it goes,
+    /// so long chains neither blow the stack nor drop recursively
+    fn into_sorted_values(mut curr: Option<Box<BinaryNode>>) -> Vec<i32> {
+        let mut values = Vec::new();
+        let mut stack: Vec<Box<BinaryNode>> = Vec::new();
+
+        loop {
+            while let Some(mut node) = curr {
+                curr = node.left.take();
+                stack.push(node);
+            }
+
+            let Some(mut node) = stack.pop() else {
+                break;
+            };
+
+            values.push(node.value);
+            curr = node.right.take();
+        }
+
+        values
+    }
+
+    /// number of nodes on the longest root-to-leaf path
+    pub fn height(node: Option<&BinaryNode>) -> usize {
+        node.map_or(0, |n| {
+            1 + Self::height(n.left.as_deref()).max(Self::height(n.right.as_deref()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// true when every node's subtrees differ in height by at most one
+    fn is_balanced(node: Option<&BinaryNode>) -> bool {
+        node.is_none_or(|n| {
+            let left = BinaryNode::height(n.left.as_deref());
+            let right = BinaryNode::height(n.right.as_deref());
+
+            left.abs_diff(right) <= 1
+                && is_balanced(n.left.as_deref())
+                && is_balanced(n.right.as_deref())
+        })
+    }
+
+    fn optimal_height(len: usize) -> usize {
+        (usize::BITS - len.leading_zeros()) as usize
+    }
+
+    #[test]
+    fn test_from_sorted_empty() {
+        assert_eq!(BinaryNode::from_sorted(&[]), None);
+    }
+
+    #[test]
+    fn test_from_sorted_is_balanced() {
+        for len in 1..=64 {
+            let values: Vec<i32> = (0..len).collect();
+            let tree = BinaryNode::from_sorted(&values);
+
+            assert_eq!(BinaryNode::sorted_values(tree.as_deref()), values);
+            assert!(is_balanced(tree.as_deref()), "unbalanced for len {len}");
+            assert_eq!(
+                BinaryNode::height(tree.as_deref()),
+                optimal_height(len as usize)
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_sorted_shape() {
+        //       4
+        //      / \
+        //     2   6
+        //    / \ / \
+        //   1  3 5  7
+        let expected = BinaryNode::with_children(
+            4,
+            Some(Box::new(BinaryNode::with_children(
+                2,
+                Some(Box::new(BinaryNode::new(1))),
+                Some(Box::new(BinaryNode::new(3))),
+            ))),
+            Some(Box::new(BinaryNode::with_children(
+                6,
+                Some(Box::new(BinaryNode::new(5))),
+                Some(Box::new(BinaryNode::new(7))),
+            ))),
+        );
+
+        let tree = BinaryNode::from_sorted(&[1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(tree.as_deref(), Some(&expected));
+    }
+
+    #[test]
+    fn test_from_sorted_iter_drops_duplicates() {
+        let tree = BinaryNode::from_sorted_iter([1, 1, 2, 3, 3, 3, 4]);
+        assert_eq!(BinaryNode::sorted_values(tree.as_deref()), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "sorted in ascending order")]
+    fn test_from_sorted_iter_rejects_unsorted_input() {
+        BinaryNode::from_sorted_iter([1, 3, 2, 3]);
+    }
+
+    #[test]
+    fn test_merge_two_trees() {
+        let a = BinaryNode::from_sorted(&[1, 3, 5, 7, 9]);
+
+        let mut b = BinaryNode::new(6);
+        b.insert(2);
+        b.insert(10);
+        b.insert(4);
+        b.insert(5);
+
+        let merged = BinaryNode::merge(a.as_deref(), Some(&b));
+
+        assert_eq!(
+            BinaryNode::sorted_values(merged.as_deref()),
+            vec![1, 2, 3, 4, 5, 6, 7, 9, 10]
+        );
+        assert!(is_balanced(merged.as_deref()));
+    }
+
+    #[test]
+    fn test_merge_with_empty() {
+        let a = BinaryNode::from_sorted(&[1, 2, 3]);
+
+        assert_eq!(BinaryNode::merge(None, None), None);
+        assert_eq!(
+            BinaryNode::sorted_values(BinaryNode::merge(a.as_deref(), None).as_deref()),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            BinaryNode::sorted_values(BinaryNode::merge(None, a.as_deref()).as_deref()),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_rebalance_degenerate_tree() {
+        // 1 -> 2 -> 3 -> ... all hanging off the right
+        let mut root = BinaryNode::new(0);
+        for value in 1..1000 {
+            root.insert(value);
+        }
+
+        let mut tree = Some(Box::new(root));
+        assert_eq!(BinaryNode::height(tree.as_deref()), 1000);
+
+        BinaryNode::rebalance(&mut tree);
+
+        assert!(is_balanced(tree.as_deref()));
+        assert_eq!(BinaryNode::height(tree.as_deref()), optimal_height(1000));
+        assert_eq!(
+            BinaryNode::sorted_values(tree.as_deref()),
+            (0..1000).collect::<Vec<_>>()
+        );
+        assert!(tree.as_ref().unwrap().contains(999));
+    }
+
+    #[test]
+    fn test_rebalance_empty() {
+        let mut tree: Option<Box<BinaryNode>> = None;
+        BinaryNode::rebalance(&mut tree);
+        assert_eq!(tree, None);
+    }
+}
@@ -1,3 +1,8 @@
+use std::cmp::Ordering;
+use std::ops::{Range, RangeInclusive};
+
+use crate::tracer::{TraceEvent, Tracer};
+
 fn binary_search(hasystack: &[u32; 5], needle: u32) -> bool {
     let mut low = 0;
     let mut high = hasystack.len();
@@ -19,9 +24,260 @@ fn binary_search(hasystack: &[u32; 5], needle: u32) -> bool {
     false
 }
 
+/// binary search over any sorted slice
+/// returns `Ok(index)` of a matching element, or `Err(index)` where the needle
+/// could be inserted to keep the slice sorted
+pub fn search<T: Ord>(haystack: &[T], needle: &T) -> Result<usize, usize> {
+    search_by(haystack, |value| value.cmp(needle))
+}
+
+/// like `search`, but `compare` tells how an element orders against the target
+pub fn search_by<T, F>(haystack: &[T], mut compare: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    search_indices(haystack.len(), |idx| compare(&haystack[idx]))
+}
+
+/// the search loop itself, `compare` receives the index to probe
+fn search_indices<F>(len: usize, mut compare: F) -> Result<usize, usize>
+where
+    F: FnMut(usize) -> Ordering,
+{
+    let mut low = 0;
+    let mut high = len;
+
+    while low < high {
+        let middle = low + (high - low) / 2;
+
+        match compare(middle) {
+            Ordering::Equal => return Ok(middle),
+            Ordering::Greater => high = middle,
+            Ordering::Less => low = middle + 1,
+        }
+    }
+
+    Err(low)
+}
+
+/// same as `search`, reporting every probed index to the tracer
+pub fn search_traced<T: Ord>(
+    haystack: &[T],
+    needle: &T,
+    tracer: &mut impl Tracer,
+) -> Result<usize, usize> {
+    search_indices(haystack.len(), |idx| {
+        tracer.trace(TraceEvent::Probe(idx));
+        haystack[idx].cmp(needle)
+    })
+}
+
+/// like `search`, for a slice sorted by the key `f` extracts
+pub fn search_by_key<T, K, F>(haystack: &[T], key: &K, mut f: F) -> Result<usize, usize>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    search_by(haystack, |value| f(value).cmp(key))
+}
+
+/// index of the first element for which `pred` is false,
+/// given a slice where `pred` holds for a prefix and fails for the rest
+pub fn partition_point<T, P>(haystack: &[T], mut pred: P) -> usize
+where
+    P: FnMut(&T) -> bool,
+{
+    let mut low = 0;
+    let mut high = haystack.len();
+
+    while low < high {
+        let middle = low + (high - low) / 2;
+
+        if pred(&haystack[middle]) {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+
+    low
+}
+
+/// index of the first element not less than the needle
+pub fn lower_bound<T: Ord>(haystack: &[T], needle: &T) -> usize {
+    lower_bound_by(haystack, |value| value.cmp(needle))
+}
+
+pub fn lower_bound_by<T, F>(haystack: &[T], mut compare: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    partition_point(haystack, |value| compare(value) == Ordering::Less)
+}
+
+pub fn lower_bound_by_key<T, K, F>(haystack: &[T], key: &K, mut f: F) -> usize
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    lower_bound_by(haystack, |value| f(value).cmp(key))
+}
+
+/// index of the first element greater than the needle
+pub fn upper_bound<T: Ord>(haystack: &[T], needle: &T) -> usize {
+    upper_bound_by(haystack, |value| value.cmp(needle))
+}
+
+pub fn upper_bound_by<T, F>(haystack: &[T], mut compare: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    partition_point(haystack, |value| compare(value) != Ordering::Greater)
+}
+
+pub fn upper_bound_by_key<T, K, F>(haystack: &[T], key: &K, mut f: F) -> usize
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    upper_bound_by(haystack, |value| f(value).cmp(key))
+}
+
+/// range of every element equal to the needle, empty at the insertion point if none
+pub fn equal_range<T: Ord>(haystack: &[T], needle: &T) -> Range<usize> {
+    equal_range_by(haystack, |value| value.cmp(needle))
+}
+
+pub fn equal_range_by<T, F>(haystack: &[T], mut compare: F) -> Range<usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let start = lower_bound_by(haystack, &mut compare);
+    // the upper bound can only lie at or after the lower one
+    let end = start + upper_bound_by(&haystack[start..], &mut compare);
+
+    start..end
+}
+
+pub fn equal_range_by_key<T, K, F>(haystack: &[T], key: &K, mut f: F) -> Range<usize>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    equal_range_by(haystack, |value| f(value).cmp(key))
+}
+
+/// integer domains `partition_point_in` can bisect
+pub trait Bisect: Copy + Ord {
+    /// floor of the average of the two values, without overflowing
+    fn midpoint_floor(self, other: Self) -> Self;
+    fn successor(self) -> Self;
+}
+
+macro_rules! impl_bisect {
+    ($($t:ty),*) => {
+        $(
+            impl Bisect for $t {
+                // shared bits plus half of the differing bits never leaves the type's range
+                fn midpoint_floor(self, other: Self) -> Self {
+                    (self & other) + ((self ^ other) >> 1)
+                }
+
+                fn successor(self) -> Self {
+                    self + 1
+                }
+            }
+        )*
+    };
+}
+
+impl_bisect!(i64, u64, usize);
+
+/// binary search on the answer: first value in `range` for which `pred` is false,
+/// given `pred` holds for some prefix of the range and fails for the rest
+/// returns `None` when `pred` holds for the whole range
+pub fn partition_point_in<T, P>(range: RangeInclusive<T>, mut pred: P) -> Option<T>
+where
+    T: Bisect,
+    P: FnMut(T) -> bool,
+{
+    let (mut low, mut high) = range.into_inner();
+
+    if low > high || pred(high) {
+        return None;
+    }
+
+    // `pred(high)` is false from here on, so the answer is always in `low..=high`
+    while low < high {
+        let middle = low.midpoint_floor(high);
+
+        if pred(middle) {
+            // middle < high, so this can't overflow
+            low = middle.successor();
+        } else {
+            high = middle;
+        }
+    }
+
+    Some(low)
+}
+
+/// when `partition_point_f64` stops narrowing the boundary
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Precision {
+    /// stop once the bracket around the boundary is at most this wide
+    Tolerance(f64),
+    /// stop after this many halvings
+    Iterations(usize),
+}
+
+/// approximates the point in `[low, high]` where `pred` switches from true to false
+/// the result is the smallest value checked that is on the false side, so it
+/// is `high` itself when `pred` holds everywhere
+///
+/// also stops once no float fits between the bracket ends, so a zero tolerance
+/// gives the exact boundary
+///
+/// infinite bounds are clamped to `±f64::MAX`, halfway between them is NaN
+pub fn partition_point_f64<P>(low: f64, high: f64, precision: Precision, mut pred: P) -> f64
+where
+    P: FnMut(f64) -> bool,
+{
+    assert!(low <= high, "low must not exceed high");
+
+    let mut low = low.clamp(f64::MIN, f64::MAX);
+    let mut high = high.clamp(f64::MIN, f64::MAX);
+
+    let mut iterations = 0;
+
+    loop {
+        let done = match precision {
+            Precision::Tolerance(tolerance) => high - low <= tolerance,
+            Precision::Iterations(limit) => iterations >= limit,
+        };
+
+        // halving each end first keeps `-MAX..MAX` from overflowing to infinity
+        let middle = low / 2.0 + high / 2.0;
+
+        if done || middle <= low || middle >= high {
+            return high;
+        }
+
+        if pred(middle) {
+            low = middle;
+        } else {
+            high = middle;
+        }
+
+        iterations += 1;
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::binary_search;
+    use super::*;
+    use crate::rng::Rng;
+    use crate::tracer::{CountingTracer, RecordingTracer};
 
     #[test]
     fn it_can_perform_binary_search() {
@@ -40,4 +296,228 @@ mod tests {
 
         assert!(!result);
     }
+
+    #[test]
+    fn it_returns_index_or_insertion_point() {
+        let arr = [1, 3, 5, 7, 9];
+
+        assert_eq!(search(&arr, &1), Ok(0));
+        assert_eq!(search(&arr, &7), Ok(3));
+        assert_eq!(search(&arr, &0), Err(0));
+        assert_eq!(search(&arr, &4), Err(2));
+        assert_eq!(search(&arr, &10), Err(5));
+        assert_eq!(search(&[] as &[i32], &1), Err(0));
+    }
+
+    #[test]
+    fn it_finds_bounds_around_duplicates() {
+        let arr = [1, 2, 2, 2, 3, 5];
+
+        assert_eq!(lower_bound(&arr, &2), 1);
+        assert_eq!(upper_bound(&arr, &2), 4);
+        assert_eq!(equal_range(&arr, &2), 1..4);
+        assert_eq!(equal_range(&arr, &4), 5..5);
+        assert_eq!(equal_range(&arr, &0), 0..0);
+        assert_eq!(equal_range(&arr, &9), 6..6);
+    }
+
+    #[test]
+    fn it_searches_structs_by_key() {
+        #[derive(Debug)]
+        struct Person {
+            name: &'static str,
+            age: u32,
+        }
+
+        let people = [
+            Person {
+                name: "ana",
+                age: 21,
+            },
+            Person {
+                name: "bob",
+                age: 30,
+            },
+            Person {
+                name: "cid",
+                age: 30,
+            },
+            Person {
+                name: "dan",
+                age: 42,
+            },
+        ];
+
+        let idx = search_by_key(&people, &42, |p| p.age).unwrap();
+        assert_eq!(people[idx].name, "dan");
+
+        assert_eq!(search_by_key(&people, &25, |p| p.age), Err(1));
+        assert_eq!(lower_bound_by_key(&people, &30, |p| p.age), 1);
+        assert_eq!(upper_bound_by_key(&people, &30, |p| p.age), 3);
+        assert_eq!(equal_range_by_key(&people, &30, |p| p.age), 1..3);
+
+        // reversed comparator for a slice sorted descending
+        let desc = [9, 7, 7, 4, 1];
+        assert_eq!(equal_range_by(&desc, |v| 7.cmp(v)), 1..3);
+    }
+
+    #[test]
+    fn it_matches_std_on_random_inputs() {
+        let mut rng = Rng::new(32);
+
+        for len in 0..80 {
+            // a narrow value range guarantees plenty of duplicates
+            let mut arr = rng.vec_i32(len, 0..20);
+            arr.sort();
+
+            for needle in -1..21 {
+                match (search(&arr, &needle), arr.binary_search(&needle)) {
+                    (Ok(idx), Ok(_)) => assert_eq!(arr[idx], needle),
+                    (ours, theirs) => assert_eq!(ours, theirs),
+                }
+
+                let lower = arr.partition_point(|v| *v < needle);
+                let upper = arr.partition_point(|v| *v <= needle);
+
+                assert_eq!(lower_bound(&arr, &needle), lower);
+                assert_eq!(upper_bound(&arr, &needle), upper);
+                assert_eq!(equal_range(&arr, &needle), lower..upper);
+            }
+        }
+    }
+
+    #[test]
+    fn it_traces_probed_indices() {
+        let arr = [1, 3, 5, 7, 9, 11, 13];
+        let mut recorder = RecordingTracer::default();
+
+        assert_eq!(search_traced(&arr, &11, &mut recorder), Ok(5));
+        assert_eq!(
+            recorder.events,
+            vec![TraceEvent::Probe(3), TraceEvent::Probe(5)]
+        );
+
+        let mut counter = CountingTracer::default();
+        let big: Vec<u32> = (0..1024).collect();
+        assert_eq!(search_traced(&big, &2000, &mut counter), Err(1024));
+        assert_eq!(counter.probes, 10);
+    }
+
+    #[test]
+    fn it_finds_partition_point_at_i64_extremes() {
+        let full = i64::MIN..=i64::MAX;
+
+        assert_eq!(partition_point_in(full.clone(), |x| x < 0), Some(0));
+        assert_eq!(
+            partition_point_in(full.clone(), |x| x < i64::MAX),
+            Some(i64::MAX)
+        );
+        assert_eq!(
+            partition_point_in(full.clone(), |x| x < i64::MIN + 1),
+            Some(i64::MIN + 1)
+        );
+        assert_eq!(partition_point_in(full.clone(), |_| false), Some(i64::MIN));
+        assert_eq!(partition_point_in(full, |_| true), None);
+        assert_eq!(
+            partition_point_in(RangeInclusive::new(-5i64, -6), |_| true),
+            None
+        );
+    }
+
+    #[test]
+    fn it_finds_partition_point_at_unsigned_extremes() {
+        assert_eq!(
+            partition_point_in(0..=u64::MAX, |x| x < u64::MAX),
+            Some(u64::MAX)
+        );
+        assert_eq!(
+            partition_point_in(0..=u64::MAX, |x| x <= u64::MAX / 2),
+            Some(u64::MAX / 2 + 1)
+        );
+        assert_eq!(
+            partition_point_in(u64::MAX..=u64::MAX, |_| false),
+            Some(u64::MAX)
+        );
+        assert_eq!(partition_point_in(0..=usize::MAX, |x| x < 1), Some(1));
+        assert_eq!(partition_point_in(0..=usize::MAX, |_| true), None);
+    }
+
+    #[test]
+    fn it_searches_on_the_answer() {
+        // integer square root: the last x with x * x <= n
+        let isqrt = |n: u64| {
+            partition_point_in(0..=n, |x| x.checked_mul(x).is_some_and(|sq| sq <= n))
+                .map_or(n, |first_too_big| first_too_big - 1)
+        };
+
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(1), 1);
+        assert_eq!(isqrt(99), 9);
+        assert_eq!(isqrt(100), 10);
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+
+        // agrees with slice partition_point on random monotone predicates
+        let mut rng = Rng::new(37);
+        for _ in 0..200 {
+            let mut arr = rng.vec_i32(50, -100..100);
+            arr.sort();
+            let cut = rng.range_i32(-110..110);
+
+            let expected = arr.partition_point(|v| *v < cut);
+            let found = partition_point_in(0..=arr.len() - 1, |i| arr[i] < cut);
+
+            assert_eq!(found.unwrap_or(arr.len()), expected);
+            assert_eq!(partition_point(&arr, |v| *v < cut), expected);
+        }
+    }
+
+    #[test]
+    fn it_finds_partition_point_over_floats() {
+        let sqrt2 = partition_point_f64(0.0, 2.0, Precision::Tolerance(1e-12), |x| x * x < 2.0);
+        assert!((sqrt2 - 2f64.sqrt()).abs() <= 1e-12);
+        assert!(sqrt2 * sqrt2 >= 2.0, "result is on the false side");
+
+        let cbrt = partition_point_f64(0.0, 10.0, Precision::Iterations(60), |x| x * x * x < 27.0);
+        assert!((cbrt - 3.0).abs() < 1e-12);
+
+        // zero tolerance narrows down to adjacent floats
+        let exact = partition_point_f64(0.0, 1.0, Precision::Tolerance(0.0), |x| x < 0.3);
+        assert_eq!(exact, 0.3);
+
+        assert_eq!(
+            partition_point_f64(0.0, 1.0, Precision::Iterations(0), |x| x < 0.5),
+            1.0
+        );
+        assert_eq!(
+            partition_point_f64(0.0, 1.0, Precision::Tolerance(1e-9), |_| true),
+            1.0
+        );
+    }
+
+    #[test]
+    fn it_finds_partition_point_across_the_whole_float_range() {
+        let found = partition_point_f64(-f64::MAX, f64::MAX, Precision::Tolerance(0.0), |x| {
+            x < 1e300
+        });
+        assert_eq!(found, 1e300);
+
+        let found = partition_point_f64(-f64::MAX, f64::MAX, Precision::Tolerance(0.0), |x| {
+            x < -1e-300
+        });
+        assert_eq!(found, -1e-300);
+    }
+
+    #[test]
+    fn it_clamps_infinite_bounds() {
+        let found = partition_point_f64(
+            f64::NEG_INFINITY,
+            f64::INFINITY,
+            Precision::Tolerance(1e-9),
+            |x| x < 3.0,
+        );
+        assert!((found - 3.0).abs() <= 1e-9, "{found}");
+
+        let found = partition_point_f64(0.0, f64::INFINITY, Precision::Tolerance(0.0), |_| true);
+        assert_eq!(found, f64::MAX);
+    }
 }
@@ -11,7 +11,7 @@ fn walk(curr: Option<&BinaryNode>, path: &mut Vec<i32>) {
     }
 }
 
-fn in_order_search(head: Option<&BinaryNode>) -> Vec<i32> {
+pub fn in_order_search(head: Option<&BinaryNode>) -> Vec<i32> {
     let mut path: Vec<i32> = Vec::new();
     walk(head, &mut path);
     path
@@ -20,6 +20,8 @@ fn in_order_search(head: Option<&BinaryNode>) -> Vec<i32> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rng::Rng;
+    use crate::tree_gen::{Chain, degenerate_chain, random_bst, random_tree};
 
     // Helper function to create a new node
     fn new_node(value: i32) -> Box<BinaryNode> {
@@ -109,4 +111,65 @@ mod tests {
         assert_eq!(result, vec![1, 2, 3]);
         println!("✓ Unbalanced tree test passed");
     }
+
+    // reference in-order walk using an explicit stack
+    fn iterative_in_order(head: Option<&BinaryNode>) -> Vec<i32> {
+        let mut path = Vec::new();
+        let mut stack: Vec<&BinaryNode> = Vec::new();
+        let mut curr = head;
+
+        loop {
+            while let Some(node) = curr {
+                stack.push(node);
+                curr = node.left.as_deref();
+            }
+
+            let Some(node) = stack.pop() else {
+                break;
+            };
+
+            path.push(node.value);
+            curr = node.right.as_deref();
+        }
+
+        path
+    }
+
+    #[test]
+    fn test_matches_reference_on_random_trees() {
+        let mut rng = Rng::new(26);
+
+        for len in 0..100 {
+            let tree = random_tree(&mut rng, len, -50..50);
+            assert_eq!(
+                in_order_search(tree.as_deref()),
+                iterative_in_order(tree.as_deref())
+            );
+        }
+    }
+
+    #[test]
+    fn test_random_bst_in_order_is_sorted() {
+        let mut rng = Rng::new(27);
+
+        for len in 0..100 {
+            let tree = random_bst(&mut rng, len);
+            let result = in_order_search(tree.as_deref());
+
+            let mut sorted = result.clone();
+            sorted.sort();
+            assert_eq!(result, sorted);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_chains_in_order() {
+        for chain in [Chain::Left, Chain::Right, Chain::ZigZag] {
+            let tree = degenerate_chain(500, chain);
+            assert_eq!(
+                in_order_search(tree.as_deref()),
+                (0..500).collect::<Vec<_>>()
+            );
+        }
+    }
 }
@@ -22,6 +22,8 @@ fn post_order_search(head: Option<&BinaryNode>) -> Vec<i32> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rng::Rng;
+    use crate::tree_gen::random_tree;
 
     // Helper function to create a new node
     fn new_node(value: i32) -> Box<BinaryNode> {
@@ -112,4 +114,38 @@ mod tests {
         assert_eq!(result, vec![1, 2, 3]);
         println!("✓ Post-order unbalanced tree test passed");
     }
+
+    // reference post-order: a root-right-left walk reversed
+    fn iterative_post_order(head: Option<&BinaryNode>) -> Vec<i32> {
+        let mut path = Vec::new();
+        let mut stack: Vec<&BinaryNode> = head.into_iter().collect();
+
+        while let Some(node) = stack.pop() {
+            path.push(node.value);
+
+            if let Some(left) = node.left.as_deref() {
+                stack.push(left);
+            }
+
+            if let Some(right) = node.right.as_deref() {
+                stack.push(right);
+            }
+        }
+
+        path.reverse();
+        path
+    }
+
+    #[test]
+    fn test_post_order_matches_reference_on_random_trees() {
+        let mut rng = Rng::new(29);
+
+        for len in 0..100 {
+            let tree = random_tree(&mut rng, len, -50..50);
+            assert_eq!(
+                post_order_search(tree.as_deref()),
+                iterative_post_order(tree.as_deref())
+            );
+        }
+    }
 }
@@ -34,7 +34,12 @@ pub fn pre_order_search_direct(head: Option<&BinaryNode>) -> Vec<i32> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{binary_node::BinaryNode, bt_pre_order::pre_order_search};
+    use crate::{
+        binary_node::BinaryNode,
+        bt_pre_order::{pre_order_search, pre_order_search_direct},
+        rng::Rng,
+        tree_gen::{complete_tree, random_tree},
+    };
 
     #[test]
     fn test_preorder_traversal() {
@@ -74,4 +79,48 @@ mod tests {
         let result = pre_order_search(tree.as_deref());
         assert_eq!(result, vec![42]);
     }
+
+    // reference pre-order walk using an explicit stack
+    fn iterative_pre_order(head: Option<&BinaryNode>) -> Vec<i32> {
+        let mut path = Vec::new();
+        let mut stack: Vec<&BinaryNode> = head.into_iter().collect();
+
+        while let Some(node) = stack.pop() {
+            path.push(node.value);
+
+            if let Some(right) = node.right.as_deref() {
+                stack.push(right);
+            }
+
+            if let Some(left) = node.left.as_deref() {
+                stack.push(left);
+            }
+        }
+
+        path
+    }
+
+    #[test]
+    fn test_matches_reference_on_random_trees() {
+        let mut rng = Rng::new(28);
+
+        for len in 0..100 {
+            let tree = random_tree(&mut rng, len, -50..50);
+            let expected = iterative_pre_order(tree.as_deref());
+
+            assert_eq!(pre_order_search(tree.as_deref()), expected);
+            assert_eq!(pre_order_search_direct(tree.as_deref()), expected);
+        }
+    }
+
+    #[test]
+    fn test_complete_tree_pre_order() {
+        //       1
+        //      / \
+        //     2   3
+        //    / \
+        //   4   5
+        let tree = complete_tree(5);
+        assert_eq!(pre_order_search(tree.as_deref()), vec![1, 2, 4, 5, 3]);
+    }
 }
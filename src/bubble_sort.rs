@@ -1,3 +1,8 @@
+use std::cmp::Ordering;
+
+use crate::tracer::{NoopTracer, TraceEvent, Tracer};
+
+#[allow(clippy::manual_swap)]
 fn bubble_sort(arr: &mut [i32]) {
     let mut i = 0;
 
@@ -17,6 +22,74 @@ fn bubble_sort(arr: &mut [i32]) {
     }
 }
 
+/// same as `bubble_sort_idiomatic`, reporting every comparison and swap to the tracer
+fn bubble_sort_traced(arr: &mut [i32], tracer: &mut impl Tracer) {
+    bubble_sort_by_traced(arr, i32::cmp, tracer);
+}
+
+/// bubble sort over any element type, ordered by `compare`
+/// stable, since only strictly out of order neighbours are swapped
+pub fn bubble_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    bubble_sort_by_traced(arr, compare, &mut NoopTracer);
+}
+
+pub fn bubble_sort_by_traced<T, F>(arr: &mut [T], mut compare: F, tracer: &mut impl Tracer)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in 0..arr.len() {
+        for j in 0..arr.len() - 1 - i {
+            tracer.trace(TraceEvent::Compare(j, j + 1));
+            if compare(&arr[j], &arr[j + 1]) == Ordering::Greater {
+                tracer.trace(TraceEvent::Swap(j, j + 1));
+                arr.swap(j, j + 1);
+            }
+        }
+    }
+}
+
+/// bubble sort that stops as soon as a pass makes no swaps, so sorted input
+/// costs a single pass, and that skips the tail the last swap left in place
+pub fn bubble_sort_early_exit<T: Ord>(arr: &mut [T]) {
+    bubble_sort_early_exit_by(arr, T::cmp);
+}
+
+pub fn bubble_sort_early_exit_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    bubble_sort_early_exit_by_traced(arr, compare, &mut NoopTracer);
+}
+
+pub fn bubble_sort_early_exit_by_traced<T, F>(
+    arr: &mut [T],
+    mut compare: F,
+    tracer: &mut impl Tracer,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // everything from `end` on is in its final place
+    let mut end = arr.len();
+
+    while end > 1 {
+        let mut last_swap = 0;
+
+        for j in 0..end - 1 {
+            tracer.trace(TraceEvent::Compare(j, j + 1));
+            if compare(&arr[j], &arr[j + 1]) == Ordering::Greater {
+                tracer.trace(TraceEvent::Swap(j, j + 1));
+                arr.swap(j, j + 1);
+                last_swap = j + 1;
+            }
+        }
+
+        end = last_swap;
+    }
+}
+
 #[allow(dead_code)]
 fn bubble_sort_idiomatic(arr: &mut [i32]) {
     for i in 0..arr.len() {
@@ -28,55 +101,129 @@ fn bubble_sort_idiomatic(arr: &mut [i32]) {
     }
 }
 
+/// the bubble sort test cases, shared by every sort over `i32`
 #[cfg(test)]
-mod tests {
-    use super::bubble_sort;
-
-    #[test]
-    fn it_can_sorted_an_array() {
+pub(crate) mod sort_cases {
+    pub fn check_unsorted_array(sort: fn(&mut [i32])) {
         // Test Case 1: Normal unsorted array
 
         let mut arr = [64, 34, 25, 12, 22, 11, 90];
         let expected = [11, 12, 22, 25, 34, 64, 90];
 
-        bubble_sort(&mut arr);
+        sort(&mut arr);
 
         assert_eq!(arr, expected);
     }
 
-    #[test]
-    fn it_can_run_edge_cases() {
+    pub fn check_edge_cases(sort: fn(&mut [i32])) {
         // Empty array
         let mut empty: [i32; 0] = [];
-        bubble_sort(&mut empty);
+        sort(&mut empty);
         assert_eq!(empty, []);
 
         // Single element
         let mut single = [42];
-        bubble_sort(&mut single);
+        sort(&mut single);
         assert_eq!(single, [42]);
 
         // Two elements (unsorted)
         let mut two_elements = [5, 2];
-        bubble_sort(&mut two_elements);
+        sort(&mut two_elements);
         assert_eq!(two_elements, [2, 5]);
     }
 
-    #[test]
-    fn it_sorted_special_array() {
+    pub fn check_special_arrays(sort: fn(&mut [i32])) {
         // Sorted array
         let mut already_sorted = [1, 2, 3, 4, 5];
-        bubble_sort(&mut already_sorted);
+        sort(&mut already_sorted);
         assert_eq!(already_sorted, [1, 2, 3, 4, 5]);
 
         // Reverse sorted array
         let mut reverse_sorted = [5, 4, 3, 2, 1];
-        bubble_sort(&mut reverse_sorted);
+        sort(&mut reverse_sorted);
         assert_eq!(reverse_sorted, [1, 2, 3, 4, 5]);
 
         // array with duplicates
         let mut with_duplicates = [3, 1, 4, 1, 5, 9, 2, 6, 5];
-        bubble_sort(&mut with_duplicates);
+        sort(&mut with_duplicates);
         assert_eq!(with_duplicates, [1, 1, 2, 3, 4, 5, 5, 6, 9]);
     }
+
+    /// all of the cases above
+    pub fn check_all(sort: fn(&mut [i32])) {
+        check_unsorted_array(sort);
+        check_edge_cases(sort);
+        check_special_arrays(sort);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sort_cases::*;
+    use super::{bubble_sort, bubble_sort_by, bubble_sort_early_exit_by, bubble_sort_traced};
+    use crate::tracer::CountingTracer;
+
+    #[test]
+    fn it_can_sorted_an_array() {
+        check_unsorted_array(bubble_sort);
+    }
+
+    #[test]
+    fn it_can_run_edge_cases() {
+        check_edge_cases(bubble_sort);
+    }
+
+    #[test]
+    fn it_sorted_special_array() {
+        check_special_arrays(bubble_sort);
+    }
+
+    #[test]
+    fn it_counts_compares_and_swaps() {
+        let mut reverse_sorted = [5, 4, 3, 2, 1];
+        let mut counter = CountingTracer::default();
+
+        bubble_sort_traced(&mut reverse_sorted, &mut counter);
+
+        assert_eq!(reverse_sorted, [1, 2, 3, 4, 5]);
+        assert_eq!(counter.compares, 10);
+        assert_eq!(counter.swaps, 10);
+    }
+
+    #[test]
+    fn it_sorts_by_a_comparator() {
+        let mut words = ["pear", "fig", "apple", "kiwi"];
+        bubble_sort_by(&mut words, |a, b| a.len().cmp(&b.len()));
+
+        // stable, so pear stays ahead of kiwi
+        assert_eq!(words, ["fig", "pear", "kiwi", "apple"]);
+    }
+
+    #[test]
+    fn it_exits_early() {
+        check_all(super::bubble_sort_early_exit);
+
+        let count = |arr: &mut [i32]| {
+            let mut compares = 0;
+            bubble_sort_early_exit_by(arr, |a, b| {
+                compares += 1;
+                a.cmp(b)
+            });
+            compares
+        };
+
+        // one pass over sorted input
+        let mut sorted: Vec<i32> = (0..100).collect();
+        assert_eq!(count(&mut sorted), 99);
+
+        // the last swap of the first pass shows the rest is already in place
+        let mut one_off: Vec<i32> = (0..100).collect();
+        one_off.swap(0, 1);
+        assert_eq!(count(&mut one_off), 99);
+        assert_eq!(one_off, (0..100).collect::<Vec<_>>());
+
+        // reverse input is still quadratic
+        let mut reverse: Vec<i32> = (0..100).rev().collect();
+        assert_eq!(count(&mut reverse), 100 * 99 / 2);
+    }
 }
@@ -21,6 +21,8 @@ fn compare(a: Option<&BinaryNode>, b: Option<&BinaryNode>) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rng::Rng;
+    use crate::tree_gen::{Chain, degenerate_chain, random_bst, random_tree};
 
     #[test]
     fn test_compare_approaches() {
@@ -117,4 +119,44 @@ mod tests {
         assert!(tree1 == tree2);
         assert!(tree1 != tree3);
     }
+
+    #[test]
+    fn test_compare_matches_partial_eq_on_random_trees() {
+        let mut rng = Rng::new(30);
+
+        for _ in 0..500 {
+            // small sizes and value ranges so equal pairs actually show up
+            let a_len = rng.range_usize(0..4);
+            let b_len = rng.range_usize(0..4);
+            let a = random_tree(&mut rng, a_len, 0..2);
+            let b = random_tree(&mut rng, b_len, 0..2);
+
+            assert_eq!(compare(a.as_deref(), b.as_deref()), a == b);
+        }
+    }
+
+    #[test]
+    fn test_compare_clone_and_mutated_clone() {
+        let mut rng = Rng::new(31);
+
+        for len in 1..50 {
+            let tree = random_bst(&mut rng, len);
+            let mut copy = tree.clone();
+
+            assert!(compare(tree.as_deref(), copy.as_deref()));
+
+            copy.as_mut().unwrap().value += 1;
+            assert!(!compare(tree.as_deref(), copy.as_deref()));
+        }
+    }
+
+    #[test]
+    fn test_compare_same_values_different_shape() {
+        for len in 2..20 {
+            let left = degenerate_chain(len, Chain::Left);
+            let right = degenerate_chain(len, Chain::Right);
+
+            assert!(!compare(left.as_deref(), right.as_deref()));
+        }
+    }
 }
@@ -1,4 +1,15 @@
 use crate::binary_node::BinaryNode;
+use crate::bt_in_order::in_order_search;
+
+/// operations shared by the search trees in this crate,
+/// so the same test suites can run against each of them
+pub trait SearchTree {
+    fn contains(&self, value: i32) -> bool;
+    fn insert(&mut self, value: i32);
+    fn delete(&mut self, value: i32) -> bool;
+    /// every stored value in ascending order
+    fn in_order(&self) -> Vec<i32>;
+}
 
 impl BinaryNode {
     /// searches for a value in the binary search tree
@@ -6,14 +17,13 @@ impl BinaryNode {
     pub fn contains(&self, needle: i32) -> bool {
         match needle.cmp(&self.value) {
             std::cmp::Ordering::Equal => true,
-            std::cmp::Ordering::Less => self
-                .left
-                .as_ref()
-                .map_or(false, |node| node.contains(needle)),
+            std::cmp::Ordering::Less => {
+                self.left.as_ref().is_some_and(|node| node.contains(needle))
+            }
             std::cmp::Ordering::Greater => self
                 .right
                 .as_ref()
-                .map_or(false, |node| node.contains(needle)),
+                .is_some_and(|node| node.contains(needle)),
         }
     }
 
@@ -152,13 +162,100 @@ impl BinaryNode {
     }
 }
 
+/// an empty tree is `None`, which also lets the root itself be deleted
+impl SearchTree for Option<Box<BinaryNode>> {
+    fn contains(&self, value: i32) -> bool {
+        dfs(self.as_deref(), value)
+    }
+
+    fn insert(&mut self, value: i32) {
+        match self {
+            Some(root) => root.insert(value),
+            None => *self = Some(Box::new(BinaryNode::new(value))),
+        }
+    }
+
+    fn delete(&mut self, value: i32) -> bool {
+        BinaryNode::delete_in_place(self, value)
+    }
+
+    fn in_order(&self) -> Vec<i32> {
+        in_order_search(self.as_deref())
+    }
+}
+
 fn dfs(head: Option<&BinaryNode>, needle: i32) -> bool {
-    head.map_or(false, |node| node.contains(needle))
+    head.is_some_and(|node| node.contains(needle))
+}
+
+/// test suites written against `SearchTree`, shared by every tree implementing it
+#[cfg(test)]
+pub(crate) mod search_tree_tests {
+    use std::collections::BTreeSet;
+
+    use super::SearchTree;
+    use crate::rng::Rng;
+
+    pub fn check_insert_and_contains<T: SearchTree>(mut tree: T) {
+        for value in [50, 30, 70, 20, 40, 60, 80, 10] {
+            tree.insert(value);
+        }
+
+        // duplicates are ignored
+        tree.insert(40);
+
+        assert_eq!(tree.in_order(), vec![10, 20, 30, 40, 50, 60, 70, 80]);
+        assert!(tree.contains(10));
+        assert!(tree.contains(80));
+        assert!(!tree.contains(25));
+        assert!(!tree.contains(100));
+    }
+
+    pub fn check_delete<T: SearchTree>(mut tree: T) {
+        for value in 1..=20 {
+            tree.insert(value);
+        }
+
+        assert!(tree.delete(10));
+        assert!(!tree.delete(10));
+        assert!(!tree.delete(100));
+        assert!(!tree.contains(10));
+
+        for value in (1..=20).filter(|v| *v != 10) {
+            assert!(tree.delete(value), "should delete {value}");
+        }
+
+        assert!(tree.in_order().is_empty());
+        assert!(!tree.delete(1));
+    }
+
+    pub fn check_random_operations<T: SearchTree>(mut tree: T, seed: u64) {
+        let mut rng = Rng::new(seed);
+        let mut reference = BTreeSet::new();
+
+        for _ in 0..2000 {
+            let value = rng.range_i32(0..300);
+
+            if rng.range_usize(0..3) == 0 {
+                assert_eq!(tree.delete(value), reference.remove(&value));
+            } else {
+                tree.insert(value);
+                reference.insert(value);
+            }
+
+            assert_eq!(tree.contains(value), reference.contains(&value));
+        }
+
+        assert_eq!(tree.in_order(), reference.into_iter().collect::<Vec<_>>());
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rng::Rng;
+    use crate::tree_gen::{Chain, degenerate_chain, random_bst};
+    use std::collections::BTreeSet;
 
     /// Helper function to create a sample BST
     ///        5
@@ -387,6 +484,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_delete_keeps_the_root() {
+        let mut tree = create_sample_tree();
+
+        assert!(!tree.delete(5), "delete can't remove the root itself");
+        assert!(tree.delete(3), "Should delete 3, which has two children");
+        assert!(tree.delete(10), "Should delete leaf 10");
+        assert!(!tree.delete(7), "7 was never in the tree");
+
+        for value in [1, 4, 5, 8] {
+            assert!(tree.contains(value), "Should still find {value}");
+        }
+        assert!(!tree.contains(3), "3 was deleted");
+        assert!(!tree.contains(10), "10 was deleted");
+    }
+
     #[test]
     fn test_delete_root_node() {
         // Test deleting root with two children
@@ -465,4 +578,58 @@ mod tests {
             "Should still find 8"
         );
     }
+
+    #[test]
+    fn test_random_operations_match_btreeset() {
+        let mut rng = Rng::new(32);
+
+        for len in 0..40 {
+            let mut tree = random_bst(&mut rng, len);
+            let mut reference: BTreeSet<i32> =
+                in_order_search(tree.as_deref()).into_iter().collect();
+
+            for _ in 0..200 {
+                let value = rng.range_i32(-450..450);
+
+                if rng.next_bool() {
+                    let deleted = BinaryNode::delete_in_place(&mut tree, value);
+                    assert_eq!(deleted, reference.remove(&value));
+                } else {
+                    match tree.as_mut() {
+                        Some(root) => root.insert(value),
+                        None => tree = Some(Box::new(BinaryNode::new(value))),
+                    }
+                    reference.insert(value);
+                }
+
+                assert_eq!(dfs(tree.as_deref(), value), reference.contains(&value));
+            }
+
+            assert_eq!(
+                in_order_search(tree.as_deref()),
+                reference.into_iter().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_delete_every_node_of_degenerate_chain() {
+        for chain in [Chain::Left, Chain::Right, Chain::ZigZag] {
+            let mut tree = degenerate_chain(200, chain);
+
+            for value in 0..200 {
+                assert!(BinaryNode::delete_in_place(&mut tree, value));
+                assert!(!dfs(tree.as_deref(), value));
+            }
+
+            assert!(tree.is_none());
+        }
+    }
+
+    #[test]
+    fn test_search_tree_suites() {
+        search_tree_tests::check_insert_and_contains(None);
+        search_tree_tests::check_delete(None);
+        search_tree_tests::check_random_operations(None, 33);
+    }
 }
@@ -0,0 +1,520 @@
+use crate::elementary_sort::{insertion_sort_by, insertion_sort_by_traced};
+use crate::tracer::{NoopTracer, TraceEvent, Tracer, WriteTracer};
+
+// MSD radix sort hands buckets this small to insertion sort
+const MSD_CUTOFF: usize = 16;
+
+// counting sort tallies at most this many values per element, or this many
+// for short inputs, anything sparser goes to radix sort instead
+const COUNTS_PER_ELEMENT: usize = 16;
+const MIN_COUNTS: usize = 1 << 16;
+
+/// counting sort: tallies every value between the min and the max, then
+/// writes them back in order
+///
+/// O(n + k) where k is `max - min + 1`, so only worth it for a narrow range,
+/// wider ones are handed to `radix_sort_i32` rather than allocating k counts
+pub fn counting_sort(arr: &mut [i32]) {
+    counting_sort_traced(arr, &mut NoopTracer);
+}
+
+/// counting sort that reports each element it tallies as a probe and every
+/// value it writes back
+pub fn counting_sort_traced(arr: &mut [i32], tracer: &mut impl WriteTracer<i32>) {
+    let (Some(&min), Some(&max)) = (arr.iter().min(), arr.iter().max()) else {
+        return;
+    };
+
+    // widened so `i32::MIN..=i32::MAX` doesn't overflow
+    let span = (max as i64 - min as i64 + 1) as usize;
+
+    if span > (arr.len() * COUNTS_PER_ELEMENT).max(MIN_COUNTS) {
+        // too sparse to tally, radix sort a copy and write that back instead
+        let mut sorted = arr.to_vec();
+        radix_sort_i32(&mut sorted);
+
+        for (idx, value) in sorted.into_iter().enumerate() {
+            tracer.write(idx, &value);
+            arr[idx] = value;
+        }
+        return;
+    }
+
+    let mut counts = vec![0usize; span];
+
+    for (idx, &value) in arr.iter().enumerate() {
+        tracer.trace(TraceEvent::Probe(idx));
+        counts[(value as i64 - min as i64) as usize] += 1;
+    }
+
+    let mut idx = 0;
+    for (offset, &count) in counts.iter().enumerate() {
+        let value = (min as i64 + offset as i64) as i32;
+        for slot in &mut arr[idx..idx + count] {
+            tracer.write(idx, &value);
+            *slot = value;
+            idx += 1;
+        }
+    }
+}
+
+/// stable counting sort on a small integer key in `0..buckets`, the building
+/// block of the LSD radix sorts
+pub fn counting_sort_by_key<T, F>(arr: &mut [T], buckets: usize, key: F)
+where
+    T: Clone,
+    F: FnMut(&T) -> usize,
+{
+    counting_sort_by_key_traced(arr, buckets, key, &mut NoopTracer);
+}
+
+/// counting sort by key that reports each element it tallies as a probe and
+/// every value it writes back
+pub fn counting_sort_by_key_traced<T, F>(
+    arr: &mut [T],
+    buckets: usize,
+    mut key: F,
+    tracer: &mut impl WriteTracer<T>,
+) where
+    T: Clone,
+    F: FnMut(&T) -> usize,
+{
+    let mut starts = vec![0usize; buckets + 1];
+    for (idx, value) in arr.iter().enumerate() {
+        tracer.trace(TraceEvent::Probe(idx));
+        starts[key(value) + 1] += 1;
+    }
+
+    // everything shares one bucket, so it is already in order
+    if starts.contains(&arr.len()) {
+        return;
+    }
+
+    // prefix sums turn the counts into where each bucket starts
+    for i in 1..starts.len() {
+        starts[i] += starts[i - 1];
+    }
+
+    let mut sorted = arr.to_vec();
+    for value in arr.iter() {
+        let bucket = key(value);
+        sorted[starts[bucket]] = value.clone();
+        starts[bucket] += 1;
+    }
+
+    for (idx, value) in sorted.into_iter().enumerate() {
+        tracer.write(idx, &value);
+        arr[idx] = value;
+    }
+}
+
+pub fn radix_sort_u32(arr: &mut [u32]) {
+    radix_sort_u32_traced(arr, &mut NoopTracer);
+}
+
+/// radix sort reporting every pass the way `counting_sort_by_key_traced` does
+pub fn radix_sort_u32_traced(arr: &mut [u32], tracer: &mut impl WriteTracer<u32>) {
+    lsd_radix_sort(arr, |value| value, tracer);
+}
+
+/// LSD radix sort on `i32`, flipping the sign bit first so negative numbers,
+/// which have it set, order below the positive ones
+pub fn radix_sort_i32(arr: &mut [i32]) {
+    radix_sort_i32_traced(arr, &mut NoopTracer);
+}
+
+pub fn radix_sort_i32_traced(arr: &mut [i32], tracer: &mut impl WriteTracer<i32>) {
+    lsd_radix_sort(arr, |value| value as u32 ^ 0x8000_0000, tracer);
+}
+
+/// least significant digit radix sort, one `counting_sort_by_key` pass per
+/// byte of the `u32` key, O(4n) whatever the values are
+///
+/// passes where every key shares the same byte leave the order as it is
+fn lsd_radix_sort<T, F>(arr: &mut [T], key: F, tracer: &mut impl WriteTracer<T>)
+where
+    T: Copy,
+    F: Fn(T) -> u32,
+{
+    for shift in (0..32).step_by(8) {
+        counting_sort_by_key_traced(
+            arr,
+            256,
+            |&value| (key(value) >> shift) as usize & 0xFF,
+            tracer,
+        );
+    }
+}
+
+/// most significant digit radix sort for strings, or anything that is bytes,
+/// ordering the same way as comparing the byte slices
+///
+/// buckets on one byte at a time from the front, so it only ever looks at
+/// the distinguishing prefix of each string. the buckets are formed in place
+/// by swapping (American flag sort), so nothing is cloned, but it isn't stable
+pub fn msd_radix_sort<S: AsRef<[u8]>>(arr: &mut [S]) {
+    msd_radix_sort_traced(arr, &mut NoopTracer);
+}
+
+/// MSD radix sort reporting each string it reads a byte of as a probe, along
+/// with its swaps and the compares and swaps of the insertion sorts
+pub fn msd_radix_sort_traced<S: AsRef<[u8]>>(arr: &mut [S], tracer: &mut impl Tracer) {
+    // ranges still to sort, each with how many leading bytes its strings share,
+    // kept on the heap since long shared prefixes go one level deeper per byte
+    let mut pending = vec![(0..arr.len(), 0)];
+
+    while let Some((range, depth)) = pending.pop() {
+        let offset = range.start;
+        let arr = &mut arr[range];
+
+        if arr.len() <= MSD_CUTOFF {
+            insertion_sort_by_traced(
+                arr,
+                |a, b| a.as_ref()[depth..].cmp(&b.as_ref()[depth..]),
+                &mut |event: TraceEvent| tracer.trace(event.shifted(offset)),
+            );
+            continue;
+        }
+
+        // bucket 0 is for strings that end here, byte `b` goes to bucket `b + 1`
+        let bucket = |s: &S| s.as_ref().get(depth).map_or(0, |&b| b as usize + 1);
+
+        let mut bounds = [0usize; 258];
+        for (idx, s) in arr.iter().enumerate() {
+            tracer.trace(TraceEvent::Probe(offset + idx));
+            bounds[bucket(s) + 1] += 1;
+        }
+
+        for i in 1..bounds.len() {
+            bounds[i] += bounds[i - 1];
+        }
+
+        // next free slot of every bucket, each element is swapped straight into
+        // its bucket until the one that lands at the slot already belongs there
+        let mut next = bounds;
+        for b in 0..257 {
+            while next[b] < bounds[b + 1] {
+                tracer.trace(TraceEvent::Probe(offset + next[b]));
+                let target = bucket(&arr[next[b]]);
+
+                if target == b {
+                    next[b] += 1;
+                } else {
+                    tracer.trace(TraceEvent::Swap(offset + next[b], offset + next[target]));
+                    arr.swap(next[b], next[target]);
+                    next[target] += 1;
+                }
+            }
+        }
+
+        // strings that ended are all equal, every other bucket goes one byte deeper
+        for b in 1..257 {
+            if bounds[b + 1] - bounds[b] > 1 {
+                pending.push((offset + bounds[b]..offset + bounds[b + 1], depth + 1));
+            }
+        }
+    }
+}
+
+/// bucket sort for values uniformly spread over `[0, 1)`: one bucket per
+/// element, each insertion sorted, O(n) expected
+///
+/// panics on values outside `[0, 1)`
+pub fn bucket_sort(arr: &mut [f64]) {
+    bucket_sort_traced(arr, &mut NoopTracer);
+}
+
+/// bucket sort reporting each value it buckets as a probe and every value it
+/// writes back, the insertion sorts run on a copy so they aren't traced
+pub fn bucket_sort_traced(arr: &mut [f64], tracer: &mut impl WriteTracer<f64>) {
+    let len = arr.len();
+    // rounding can push values just below 1 into bucket `len`
+    let bucket = |value: f64| ((value * len as f64) as usize).min(len - 1);
+
+    let mut bounds = vec![0usize; len + 1];
+    for (idx, &value) in arr.iter().enumerate() {
+        tracer.trace(TraceEvent::Probe(idx));
+        assert!(
+            (0.0..1.0).contains(&value),
+            "bucket sort only takes values in [0, 1), got {value}"
+        );
+        bounds[bucket(value) + 1] += 1;
+    }
+
+    for i in 1..bounds.len() {
+        bounds[i] += bounds[i - 1];
+    }
+
+    let mut next = bounds.clone();
+    let mut sorted = vec![0.0; len];
+    for &value in arr.iter() {
+        let b = bucket(value);
+        sorted[next[b]] = value;
+        next[b] += 1;
+    }
+
+    for b in 0..len {
+        insertion_sort_by(&mut sorted[bounds[b]..bounds[b + 1]], f64::total_cmp);
+    }
+
+    for (idx, value) in sorted.into_iter().enumerate() {
+        tracer.write(idx, &value);
+        arr[idx] = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::tracer::CountingTracer;
+
+    #[test]
+    fn test_counting_sort() {
+        let mut rng = Rng::new(46);
+
+        for len in 0..100 {
+            let mut arr = rng.vec_i32(len, -20..20);
+            let mut expected = arr.clone();
+            expected.sort();
+
+            counting_sort(&mut arr);
+            assert_eq!(arr, expected);
+        }
+
+        // narrow ranges at either end of i32 don't overflow
+        let mut top = [i32::MAX, i32::MAX - 3, i32::MAX - 1, i32::MAX];
+        counting_sort(&mut top);
+        assert_eq!(top, [i32::MAX - 3, i32::MAX - 1, i32::MAX, i32::MAX]);
+
+        let mut bottom = [i32::MIN + 2, i32::MIN, i32::MIN + 1];
+        counting_sort(&mut bottom);
+        assert_eq!(bottom, [i32::MIN, i32::MIN + 1, i32::MIN + 2]);
+    }
+
+    #[test]
+    fn test_counting_sort_wide_span() {
+        // a count per value here would be 2^32 of them
+        let mut extremes = [i32::MAX, 0, i32::MIN];
+        counting_sort(&mut extremes);
+        assert_eq!(extremes, [i32::MIN, 0, i32::MAX]);
+
+        let mut rng = Rng::new(461);
+        let mut arr = rng.vec_i32(1000, i32::MIN..i32::MAX);
+        let mut expected = arr.clone();
+        expected.sort();
+
+        let mut counter = CountingTracer::default();
+        counting_sort_traced(&mut arr, &mut counter);
+        assert_eq!(arr, expected);
+        assert_eq!(counter.writes, 1000);
+    }
+
+    #[test]
+    fn test_counting_sort_traces_every_write() {
+        let mut arr = [3, -1, 3, 0];
+        let mut counter = CountingTracer::default();
+        counting_sort_traced(&mut arr, &mut counter);
+
+        assert_eq!(arr, [-1, 0, 3, 3]);
+        assert_eq!(counter.probes, 4);
+        assert_eq!(counter.writes, 4);
+        assert_eq!(counter.compares + counter.swaps, 0);
+    }
+
+    #[test]
+    fn test_counting_sort_by_key_is_stable() {
+        let mut records = [(2, 'a'), (0, 'b'), (2, 'c'), (1, 'd'), (0, 'e')];
+        counting_sort_by_key(&mut records, 3, |record| record.0);
+
+        assert_eq!(records, [(0, 'b'), (0, 'e'), (1, 'd'), (2, 'a'), (2, 'c')]);
+    }
+
+    #[test]
+    fn test_radix_sort_i32() {
+        let mut rng = Rng::new(460);
+
+        for len in [0, 1, 2, 10, 100, 1000] {
+            for range in [i32::MIN..i32::MAX, -300..300, 0..256] {
+                let mut arr = rng.vec_i32(len, range);
+                let mut expected = arr.clone();
+                expected.sort();
+
+                radix_sort_i32(&mut arr);
+                assert_eq!(arr, expected);
+            }
+        }
+
+        let mut edges = [0, -1, i32::MAX, i32::MIN, 1, -256, 256, i32::MIN + 1];
+        radix_sort_i32(&mut edges);
+        assert_eq!(
+            edges,
+            [i32::MIN, i32::MIN + 1, -256, -1, 0, 1, 256, i32::MAX]
+        );
+    }
+
+    #[test]
+    fn test_radix_sort_u32() {
+        let mut rng = Rng::new(461);
+
+        for len in [0, 1, 2, 10, 100, 1000] {
+            let mut arr: Vec<u32> = (0..len).map(|_| rng.next_u64() as u32).collect();
+            let mut expected = arr.clone();
+            expected.sort();
+
+            radix_sort_u32(&mut arr);
+            assert_eq!(arr, expected);
+        }
+
+        let mut edges = [u32::MAX, 0, 1 << 31, 255, 256, (1 << 31) - 1];
+        radix_sort_u32(&mut edges);
+        assert_eq!(edges, [0, 255, 256, (1 << 31) - 1, 1 << 31, u32::MAX]);
+    }
+
+    #[test]
+    fn test_msd_radix_sort() {
+        let mut rng = Rng::new(462);
+
+        let mut words = vec![
+            "banana", "", "band", "ban", "bandana", "apple", "", "b", "zebra", "über", "bañera",
+        ];
+        let mut expected = words.clone();
+        expected.sort();
+        msd_radix_sort(&mut words);
+        assert_eq!(words, expected);
+
+        for len in [0, 1, 17, 100, 2000] {
+            // short words over a small alphabet, so many share long prefixes
+            let mut arr: Vec<String> = (0..len)
+                .map(|_| {
+                    let word_len = rng.range_usize(0..8);
+                    (0..word_len)
+                        .map(|_| (b'a' + rng.below(3) as u8) as char)
+                        .collect()
+                })
+                .collect();
+            let mut expected = arr.clone();
+            expected.sort();
+
+            msd_radix_sort(&mut arr);
+            assert_eq!(arr, expected);
+        }
+    }
+
+    #[test]
+    fn test_msd_radix_sort_long_shared_prefixes() {
+        // one level per shared byte, far more than the stack could recurse
+        let prefix = "x".repeat(20_000);
+        let mut identical = vec![prefix.clone(); 40];
+        msd_radix_sort(&mut identical);
+        assert!(identical.iter().all(|s| *s == prefix));
+
+        // the same prefix with a different last byte on each
+        let mut rng = Rng::new(464);
+        let mut arr: Vec<String> = (0..40)
+            .map(|_| format!("{prefix}{}", rng.below(10)))
+            .collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        msd_radix_sort(&mut arr);
+        assert_eq!(arr, expected);
+    }
+
+    #[test]
+    fn test_bucket_sort() {
+        let mut rng = Rng::new(463);
+
+        for len in [0, 1, 2, 10, 100, 1000] {
+            let mut arr: Vec<f64> = (0..len).map(|_| rng.next_f64()).collect();
+            let mut expected = arr.clone();
+            expected.sort_by(f64::total_cmp);
+
+            bucket_sort(&mut arr);
+            assert_eq!(arr, expected);
+        }
+
+        // everything in one bucket still sorts
+        let mut clustered = [0.5003, 0.5001, 0.5002, 0.5];
+        bucket_sort(&mut clustered);
+        assert_eq!(clustered, [0.5, 0.5001, 0.5002, 0.5003]);
+    }
+
+    #[test]
+    #[should_panic(expected = "[0, 1)")]
+    fn test_bucket_sort_rejects_out_of_range() {
+        bucket_sort(&mut [0.5, 1.0]);
+    }
+
+    /// run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_against_comparison_sorts() {
+        use crate::quicksort::quick_sort_by;
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        let len = 2_000_000;
+        let mut rng = Rng::new(1);
+
+        let time = |name: &str, run: &mut dyn FnMut()| {
+            let start = Instant::now();
+            run();
+            println!("  {name:<16} {:?}", start.elapsed());
+        };
+
+        let ints = rng.vec_i32(len, i32::MIN..i32::MAX);
+        let narrow = rng.vec_i32(len, 0..1000);
+        let unsigned: Vec<u32> = (0..len).map(|_| rng.next_u64() as u32).collect();
+        let floats: Vec<f64> = (0..len).map(|_| rng.next_f64()).collect();
+        let words: Vec<String> = (0..len / 10)
+            .map(|_| format!("{:x}", rng.next_u64()))
+            .collect();
+
+        println!("{len} random i32");
+        time("quick_sort", &mut || {
+            quick_sort_by(black_box(&mut ints.clone()), i32::cmp)
+        });
+        time("sort_unstable", &mut || {
+            black_box(&mut ints.clone()).sort_unstable()
+        });
+        time("radix_sort_i32", &mut || {
+            radix_sort_i32(black_box(&mut ints.clone()))
+        });
+
+        println!("{len} i32 in 0..1000");
+        time("quick_sort", &mut || {
+            quick_sort_by(black_box(&mut narrow.clone()), i32::cmp)
+        });
+        time("counting_sort", &mut || {
+            counting_sort(black_box(&mut narrow.clone()))
+        });
+        time("radix_sort_i32", &mut || {
+            radix_sort_i32(black_box(&mut narrow.clone()))
+        });
+
+        println!("{len} random u32");
+        time("quick_sort", &mut || {
+            quick_sort_by(black_box(&mut unsigned.clone()), u32::cmp)
+        });
+        time("radix_sort_u32", &mut || {
+            radix_sort_u32(black_box(&mut unsigned.clone()))
+        });
+
+        println!("{len} f64 in [0, 1)");
+        time("quick_sort", &mut || {
+            quick_sort_by(black_box(&mut floats.clone()), f64::total_cmp)
+        });
+        time("bucket_sort", &mut || {
+            bucket_sort(black_box(&mut floats.clone()))
+        });
+
+        println!("{} hex strings", len / 10);
+        time("quick_sort", &mut || {
+            quick_sort_by(black_box(&mut words.clone()), String::cmp)
+        });
+        time("msd_radix_sort", &mut || {
+            msd_radix_sort(black_box(&mut words.clone()))
+        });
+    }
+}
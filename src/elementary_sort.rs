@@ -0,0 +1,512 @@
+use std::cmp::Ordering;
+
+use crate::tracer::{NoopTracer, TraceEvent, Tracer};
+
+/// gap sequences for shell sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GapSequence {
+    /// Shell's original n/2, n/4, ..., 1, O(n²) in the worst case
+    Shell,
+    /// Knuth's 1, 4, 13, 40, ... (3^k - 1) / 2, O(n^1.5)
+    Knuth,
+    /// Ciura's experimentally found gaps, extended by ×2.25 past the table
+    Ciura,
+}
+
+const CIURA_GAPS: [usize; 9] = [1, 4, 10, 23, 57, 132, 301, 701, 1750];
+
+impl GapSequence {
+    /// the gaps used to sort `len` elements, largest first and always ending in 1
+    pub fn gaps(self, len: usize) -> Vec<usize> {
+        let mut gaps = match self {
+            GapSequence::Shell => {
+                let mut gaps = Vec::new();
+                let mut gap = len / 2;
+                while gap > 0 {
+                    gaps.push(gap);
+                    gap /= 2;
+                }
+                gaps.reverse();
+                gaps
+            }
+            GapSequence::Knuth => {
+                let mut gaps = vec![1];
+                while gaps[gaps.len() - 1] * 3 < len {
+                    gaps.push(gaps[gaps.len() - 1] * 3 + 1);
+                }
+                gaps
+            }
+            GapSequence::Ciura => {
+                let mut gaps: Vec<usize> = CIURA_GAPS
+                    .iter()
+                    .copied()
+                    .filter(|&gap| gap < len)
+                    .collect();
+
+                if gaps.len() == CIURA_GAPS.len() {
+                    loop {
+                        let next = gaps[gaps.len() - 1] * 9 / 4;
+                        if next >= len {
+                            break;
+                        }
+                        gaps.push(next);
+                    }
+                }
+                gaps
+            }
+        };
+
+        if gaps.is_empty() {
+            gaps.push(1);
+        }
+        gaps.reverse();
+        gaps
+    }
+}
+
+pub fn insertion_sort<T: Ord>(arr: &mut [T]) {
+    insertion_sort_by(arr, T::cmp);
+}
+
+/// insertion sort, walking each element left past everything bigger
+/// stable, O(n) on sorted input and O(n²) in general
+pub fn insertion_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    insertion_sort_by_traced(arr, compare, &mut NoopTracer);
+}
+
+pub fn insertion_sort_by_traced<T, F>(arr: &mut [T], mut compare: F, tracer: &mut impl Tracer)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in 1..arr.len() {
+        let mut j = i;
+        while j > 0 {
+            tracer.trace(TraceEvent::Compare(j, j - 1));
+            if compare(&arr[j], &arr[j - 1]) != Ordering::Less {
+                break;
+            }
+            tracer.trace(TraceEvent::Swap(j - 1, j));
+            arr.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+pub fn binary_insertion_sort<T: Ord>(arr: &mut [T]) {
+    binary_insertion_sort_by(arr, T::cmp);
+}
+
+/// insertion sort that binary searches each element's slot
+/// O(n log n) comparisons, still O(n²) moves, stable
+pub fn binary_insertion_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    binary_insertion_sort_by_traced(arr, compare, &mut NoopTracer);
+}
+
+pub fn binary_insertion_sort_by_traced<T, F>(
+    arr: &mut [T],
+    mut compare: F,
+    tracer: &mut impl Tracer,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    binary_insert_tail(arr, 1, &mut compare, tracer);
+}
+
+/// inserts `arr[sorted..]` one by one into the already sorted `arr[..sorted]`,
+/// after any equal elements so it stays stable
+///
+/// each insertion is a rotation, traced as the swaps that walk the element
+/// down to its slot
+pub(crate) fn binary_insert_tail<T, F>(
+    arr: &mut [T],
+    sorted: usize,
+    compare: &mut F,
+    tracer: &mut impl Tracer,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in sorted.max(1)..arr.len() {
+        // the slot is after everything in arr[..i] that isn't bigger than arr[i]
+        let (mut low, mut high) = (0, i);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            tracer.trace(TraceEvent::Compare(i, middle));
+            if compare(&arr[i], &arr[middle]) == Ordering::Less {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+
+        for j in (low..i).rev() {
+            tracer.trace(TraceEvent::Swap(j, j + 1));
+        }
+        arr[low..=i].rotate_right(1);
+    }
+}
+
+pub fn selection_sort<T: Ord>(arr: &mut [T]) {
+    selection_sort_by(arr, T::cmp);
+}
+
+/// selection sort, swapping the minimum of the unsorted part to its front
+/// at most n - 1 swaps, but always O(n²) comparisons, not stable
+pub fn selection_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    selection_sort_by_traced(arr, compare, &mut NoopTracer);
+}
+
+pub fn selection_sort_by_traced<T, F>(arr: &mut [T], mut compare: F, tracer: &mut impl Tracer)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in 0..arr.len() {
+        let mut min = i;
+
+        for j in i + 1..arr.len() {
+            tracer.trace(TraceEvent::Compare(j, min));
+            if compare(&arr[j], &arr[min]) == Ordering::Less {
+                min = j;
+            }
+        }
+
+        if min != i {
+            tracer.trace(TraceEvent::Swap(i, min));
+            arr.swap(i, min);
+        }
+    }
+}
+
+pub fn shell_sort<T: Ord>(arr: &mut [T], gaps: GapSequence) {
+    shell_sort_by(arr, gaps, T::cmp);
+}
+
+/// shell sort: insertion sorts every gap-th element for each gap in turn,
+/// the final gap of 1 being a plain insertion sort on nearly sorted data
+/// not stable
+pub fn shell_sort_by<T, F>(arr: &mut [T], gaps: GapSequence, compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    shell_sort_by_traced(arr, gaps, compare, &mut NoopTracer);
+}
+
+pub fn shell_sort_by_traced<T, F>(
+    arr: &mut [T],
+    gaps: GapSequence,
+    mut compare: F,
+    tracer: &mut impl Tracer,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for gap in gaps.gaps(arr.len()) {
+        for i in gap..arr.len() {
+            let mut j = i;
+            while j >= gap {
+                tracer.trace(TraceEvent::Compare(j, j - gap));
+                if compare(&arr[j], &arr[j - gap]) != Ordering::Less {
+                    break;
+                }
+                tracer.trace(TraceEvent::Swap(j - gap, j));
+                arr.swap(j - gap, j);
+                j -= gap;
+            }
+        }
+    }
+}
+
+pub fn cocktail_shaker_sort<T: Ord>(arr: &mut [T]) {
+    cocktail_shaker_sort_by(arr, T::cmp);
+}
+
+/// bubble sort that alternates direction, so small elements near the end
+/// (turtles) move as fast as big ones near the front
+/// stops once a round trip makes no swaps, stable
+pub fn cocktail_shaker_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    cocktail_shaker_sort_by_traced(arr, compare, &mut NoopTracer);
+}
+
+pub fn cocktail_shaker_sort_by_traced<T, F>(arr: &mut [T], mut compare: F, tracer: &mut impl Tracer)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // arr[..start] and arr[end..] are in their final place
+    let mut start = 0;
+    let mut end = arr.len();
+
+    while start + 1 < end {
+        let mut last_swap = start;
+        for j in start..end - 1 {
+            tracer.trace(TraceEvent::Compare(j, j + 1));
+            if compare(&arr[j], &arr[j + 1]) == Ordering::Greater {
+                tracer.trace(TraceEvent::Swap(j, j + 1));
+                arr.swap(j, j + 1);
+                last_swap = j + 1;
+            }
+        }
+        end = last_swap;
+
+        if start + 1 >= end {
+            break;
+        }
+
+        let mut first_swap = end;
+        for j in (start + 1..end).rev() {
+            tracer.trace(TraceEvent::Compare(j, j - 1));
+            if compare(&arr[j], &arr[j - 1]) == Ordering::Less {
+                tracer.trace(TraceEvent::Swap(j - 1, j));
+                arr.swap(j - 1, j);
+                first_swap = j;
+            }
+        }
+        start = first_swap;
+    }
+}
+
+pub fn gnome_sort<T: Ord>(arr: &mut [T]) {
+    gnome_sort_by(arr, T::cmp);
+}
+
+/// gnome sort: step forward while the pair is in order, otherwise swap and
+/// step back, insertion sort without the inner loop, stable
+pub fn gnome_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    gnome_sort_by_traced(arr, compare, &mut NoopTracer);
+}
+
+pub fn gnome_sort_by_traced<T, F>(arr: &mut [T], mut compare: F, tracer: &mut impl Tracer)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut pos = 1;
+
+    while pos < arr.len() {
+        if pos == 0 {
+            pos += 1;
+            continue;
+        }
+
+        tracer.trace(TraceEvent::Compare(pos, pos - 1));
+        if compare(&arr[pos], &arr[pos - 1]) != Ordering::Less {
+            pos += 1;
+        } else {
+            tracer.trace(TraceEvent::Swap(pos - 1, pos));
+            arr.swap(pos - 1, pos);
+            pos -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bubble_sort::sort_cases::check_all;
+    use crate::rng::Rng;
+    use crate::tracer::CountingTracer;
+
+    type Sort = fn(&mut [i32]);
+
+    const SORTS: [(&str, Sort); 9] = [
+        ("insertion", insertion_sort::<i32>),
+        ("binary insertion", binary_insertion_sort::<i32>),
+        ("selection", selection_sort::<i32>),
+        ("shell (shell)", |arr| shell_sort(arr, GapSequence::Shell)),
+        ("shell (knuth)", |arr| shell_sort(arr, GapSequence::Knuth)),
+        ("shell (ciura)", |arr| shell_sort(arr, GapSequence::Ciura)),
+        ("cocktail shaker", cocktail_shaker_sort::<i32>),
+        ("gnome", gnome_sort::<i32>),
+        (
+            "bubble (early exit)",
+            crate::bubble_sort::bubble_sort_early_exit::<i32>,
+        ),
+    ];
+
+    type SortBy = fn(&mut [(u8, usize)], fn(&(u8, usize), &(u8, usize)) -> Ordering);
+
+    const STABLE_SORTS: [(&str, SortBy); 4] = [
+        ("insertion", insertion_sort_by),
+        ("binary insertion", binary_insertion_sort_by),
+        ("cocktail shaker", cocktail_shaker_sort_by),
+        ("gnome", gnome_sort_by),
+    ];
+
+    #[test]
+    fn test_bubble_sort_cases() {
+        for (_, sort) in SORTS {
+            check_all(sort);
+        }
+    }
+
+    #[test]
+    fn test_matches_std() {
+        let mut rng = Rng::new(45);
+
+        for len in 0..150 {
+            let input = rng.vec_i32(len, -30..30);
+            let mut expected = input.clone();
+            expected.sort();
+
+            for (name, sort) in SORTS {
+                let mut arr = input.clone();
+                sort(&mut arr);
+                assert_eq!(arr, expected, "{name} on {input:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_stable_sorts_keep_equal_keys_in_order() {
+        let mut rng = Rng::new(450);
+
+        for len in [0, 1, 2, 5, 40, 200] {
+            let input: Vec<(u8, usize)> = (0..len).map(|i| (rng.below(4) as u8, i)).collect();
+            let mut expected = input.clone();
+            expected.sort_by_key(|record| record.0);
+
+            for (name, sort) in STABLE_SORTS {
+                let mut arr = input.clone();
+                sort(&mut arr, |a, b| a.0.cmp(&b.0));
+                assert_eq!(arr, expected, "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_traced_sorts_report_every_compare() {
+        type Compare<'a> = dyn FnMut(&i32, &i32) -> Ordering + 'a;
+        type Traced = fn(&mut [i32], &mut Compare, &mut CountingTracer);
+
+        const TRACED: [(&str, Traced); 6] = [
+            ("insertion", |arr, c, t| insertion_sort_by_traced(arr, c, t)),
+            ("binary insertion", |arr, c, t| {
+                binary_insertion_sort_by_traced(arr, c, t)
+            }),
+            ("selection", |arr, c, t| selection_sort_by_traced(arr, c, t)),
+            ("shell", |arr, c, t| {
+                shell_sort_by_traced(arr, GapSequence::Ciura, c, t)
+            }),
+            ("cocktail shaker", |arr, c, t| {
+                cocktail_shaker_sort_by_traced(arr, c, t)
+            }),
+            ("gnome", |arr, c, t| gnome_sort_by_traced(arr, c, t)),
+        ];
+
+        let input = Rng::new(451).vec_i32(300, -50..50);
+
+        for (name, sort) in TRACED {
+            let mut compares = 0;
+            let mut counter = CountingTracer::default();
+            sort(
+                &mut input.clone(),
+                &mut |a, b| {
+                    compares += 1;
+                    a.cmp(b)
+                },
+                &mut counter,
+            );
+            assert_eq!(counter.compares, compares, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_gap_sequences() {
+        assert_eq!(GapSequence::Shell.gaps(100), vec![50, 25, 12, 6, 3, 1]);
+        assert_eq!(GapSequence::Knuth.gaps(100), vec![40, 13, 4, 1]);
+        assert_eq!(GapSequence::Ciura.gaps(100), vec![57, 23, 10, 4, 1]);
+        assert_eq!(
+            GapSequence::Ciura.gaps(10_000),
+            vec![8858, 3937, 1750, 701, 301, 132, 57, 23, 10, 4, 1]
+        );
+
+        for gaps in [GapSequence::Shell, GapSequence::Knuth, GapSequence::Ciura] {
+            assert_eq!(gaps.gaps(0), vec![1]);
+            assert_eq!(gaps.gaps(1), vec![1]);
+            assert_eq!(gaps.gaps(2), vec![1]);
+        }
+    }
+
+    #[test]
+    fn test_comparison_counts() {
+        type Compare<'a> = dyn FnMut(&i32, &i32) -> Ordering + 'a;
+
+        fn count(sort: fn(&mut [i32], &mut Compare), arr: &mut [i32]) -> usize {
+            let mut compares = 0;
+            sort(arr, &mut |a, b| {
+                compares += 1;
+                a.cmp(b)
+            });
+            compares
+        }
+
+        let len = 500;
+        let sorted: Vec<i32> = (0..len).collect();
+        let reverse: Vec<i32> = (0..len).rev().collect();
+        let n = len as usize;
+
+        // adaptive ones are linear on sorted input
+        assert_eq!(
+            count(|arr, c| insertion_sort_by(arr, c), &mut sorted.clone()),
+            n - 1
+        );
+        assert_eq!(
+            count(|arr, c| gnome_sort_by(arr, c), &mut sorted.clone()),
+            n - 1
+        );
+        assert_eq!(
+            count(
+                |arr, c| cocktail_shaker_sort_by(arr, c),
+                &mut sorted.clone()
+            ),
+            n - 1
+        );
+
+        // selection sort doesn't care what it's given
+        let quadratic = n * (n - 1) / 2;
+        assert_eq!(
+            count(|arr, c| selection_sort_by(arr, c), &mut sorted.clone()),
+            quadratic
+        );
+        assert_eq!(
+            count(|arr, c| selection_sort_by(arr, c), &mut reverse.clone()),
+            quadratic
+        );
+
+        // binary insertion trades compares for moves
+        let binary = count(
+            |arr, c| binary_insertion_sort_by(arr, c),
+            &mut reverse.clone(),
+        );
+        assert!(binary < n * 10, "{binary}");
+
+        // a turtle at the end costs bubble sort a pass per step, the shaker one round trip
+        let mut turtle: Vec<i32> = (1..len).chain([0]).collect();
+        let shaker = count(|arr, c| cocktail_shaker_sort_by(arr, c), &mut turtle);
+        assert!(shaker < 3 * n, "{shaker}");
+        assert_eq!(turtle, sorted);
+
+        // ciura's gaps beat shell's on random input
+        let random = Rng::new(4).vec_i32(n * 10, 0..i32::MAX);
+        let shell = count(
+            |arr, c| shell_sort_by(arr, GapSequence::Shell, c),
+            &mut random.clone(),
+        );
+        let ciura = count(
+            |arr, c| shell_sort_by(arr, GapSequence::Ciura, c),
+            &mut random.clone(),
+        );
+        assert!(ciura < shell, "ciura {ciura} vs shell {shell}");
+    }
+}
@@ -0,0 +1,371 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Lines, Write};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::quicksort::intro_sort_by;
+
+// read buffer for each run being merged, charged against the memory budget
+const RUN_BUFFER: usize = 8 * 1024;
+// most runs merged at once, well under the usual limit of 1024 open files
+const MAX_FAN_IN: usize = 256;
+
+/// limits for `external_sort`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalSortConfig {
+    /// bytes of values held in memory at once while building the sorted runs
+    pub memory_budget: usize,
+    /// where the run files are spilled, inside a directory of their own
+    pub temp_dir: PathBuf,
+}
+
+impl Default for ExternalSortConfig {
+    fn default() -> Self {
+        ExternalSortConfig {
+            memory_budget: 64 * 1024 * 1024,
+            temp_dir: std::env::temp_dir(),
+        }
+    }
+}
+
+/// what an external sort did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExternalSortStats {
+    pub values: usize,
+    pub runs: usize,
+    /// passes over the data merging runs, the last one writing the output
+    pub merge_passes: usize,
+}
+
+/// sorts a file of newline separated integers that may not fit in memory
+///
+/// reads the input in chunks of at most `memory_budget` bytes of values,
+/// sorts each with introsort and spills it to a run file, then k-way merges
+/// the runs into `output` through a min-heap holding one value per run
+///
+/// only as many runs are merged at once as their read buffers fit in the
+/// budget, at most `MAX_FAN_IN`, so more runs than that are merged in passes
+///
+/// blank lines are skipped, anything else that isn't an `i64` is an
+/// `InvalidData` error. the run files are removed whether or not it succeeds
+pub fn external_sort(
+    input: &Path,
+    output: &Path,
+    config: &ExternalSortConfig,
+) -> io::Result<ExternalSortStats> {
+    let run_dir = RunDir::create(&config.temp_dir)?;
+    let chunk_len = (config.memory_budget / size_of::<i64>()).max(1);
+
+    let mut values = 0;
+    let mut run_paths = Vec::new();
+    let mut lines = NumberLines::new(BufReader::new(File::open(input)?));
+    let mut chunk = Vec::with_capacity(chunk_len);
+
+    loop {
+        chunk.clear();
+        while chunk.len() < chunk_len {
+            match lines.next_number()? {
+                Some(value) => chunk.push(value),
+                None => break,
+            }
+        }
+
+        if chunk.is_empty() {
+            break;
+        }
+
+        values += chunk.len();
+        intro_sort_by(&mut chunk, i64::cmp);
+
+        let path = run_dir.path.join(format!("run-{}.txt", run_paths.len()));
+        write_numbers(&path, chunk.iter().copied())?;
+        run_paths.push(path);
+    }
+
+    let runs = run_paths.len();
+    let fan_in = fan_in(config.memory_budget);
+    let mut merge_passes = 1;
+    let mut next_run = runs;
+
+    while run_paths.len() > fan_in {
+        let mut merged = Vec::with_capacity(run_paths.len().div_ceil(fan_in));
+
+        for group in run_paths.chunks(fan_in) {
+            let path = run_dir.path.join(format!("run-{next_run}.txt"));
+            next_run += 1;
+
+            merge_runs(group, &path)?;
+            for done in group {
+                fs::remove_file(done)?;
+            }
+            merged.push(path);
+        }
+
+        run_paths = merged;
+        merge_passes += 1;
+    }
+
+    merge_runs(&run_paths, output)?;
+
+    Ok(ExternalSortStats {
+        values,
+        runs,
+        merge_passes,
+    })
+}
+
+/// how many runs a budget of `memory_budget` bytes can merge at once
+fn fan_in(memory_budget: usize) -> usize {
+    (memory_budget / RUN_BUFFER).clamp(2, MAX_FAN_IN)
+}
+
+/// k-way merge of sorted run files into `output`
+fn merge_runs(run_paths: &[PathBuf], output: &Path) -> io::Result<()> {
+    let mut runs = Vec::with_capacity(run_paths.len());
+    // the smallest unmerged value of every run that still has one,
+    // tagged with its run so the next value can be pulled from there
+    let mut heap = BinaryHeap::with_capacity(run_paths.len());
+
+    for (idx, path) in run_paths.iter().enumerate() {
+        let mut run = NumberLines::new(BufReader::with_capacity(RUN_BUFFER, File::open(path)?));
+
+        if let Some(value) = run.next_number()? {
+            heap.push(Reverse((value, idx)));
+        }
+        runs.push(run);
+    }
+
+    let mut out = BufWriter::new(File::create(output)?);
+
+    while let Some(Reverse((value, idx))) = heap.pop() {
+        writeln!(out, "{value}")?;
+
+        if let Some(next) = runs[idx].next_number()? {
+            heap.push(Reverse((next, idx)));
+        }
+    }
+
+    out.flush()
+}
+
+fn write_numbers(path: &Path, values: impl Iterator<Item = i64>) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+
+    for value in values {
+        writeln!(out, "{value}")?;
+    }
+
+    out.flush()
+}
+
+/// reads one integer per line, skipping blank lines
+struct NumberLines<R> {
+    lines: Lines<R>,
+    line_number: usize,
+}
+
+impl<R: BufRead> NumberLines<R> {
+    fn new(reader: R) -> Self {
+        NumberLines {
+            lines: reader.lines(),
+            line_number: 0,
+        }
+    }
+
+    fn next_number(&mut self) -> io::Result<Option<i64>> {
+        for line in self.lines.by_ref() {
+            let line = line?;
+            self.line_number += 1;
+
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            return trimmed.parse().map(Some).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "line {}: {trimmed:?} is not an integer: {err}",
+                        self.line_number
+                    ),
+                )
+            });
+        }
+
+        Ok(None)
+    }
+}
+
+/// a directory of run files that is removed along with everything in it
+/// when dropped, so an early return on error cleans up too
+struct RunDir {
+    path: PathBuf,
+}
+
+impl RunDir {
+    fn create(parent: &Path) -> io::Result<Self> {
+        // unique per process and per sort, so concurrent sorts don't collide
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+        let path = parent.join(format!("external-sort-{}-{id}", std::process::id()));
+        fs::create_dir_all(&path)?;
+
+        Ok(RunDir { path })
+    }
+}
+
+impl Drop for RunDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    /// a fresh directory straight in the system temp dir for one test,
+    /// removed again at the end of it
+    fn scratch_dir() -> RunDir {
+        RunDir::create(&std::env::temp_dir()).unwrap()
+    }
+
+    fn small_config(dir: &RunDir, values_in_memory: usize) -> ExternalSortConfig {
+        ExternalSortConfig {
+            memory_budget: values_in_memory * size_of::<i64>(),
+            temp_dir: dir.path.join("tmp"),
+        }
+    }
+
+    fn read_numbers(path: &Path) -> Vec<i64> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| line.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_sorts_generated_file_with_small_budget() {
+        let dir = scratch_dir();
+        let input = dir.path.join("input.txt");
+        let output = dir.path.join("output.txt");
+
+        let mut rng = Rng::new(47);
+        let values: Vec<i64> = (0..10_000)
+            .map(|_| rng.range_i64(i64::MIN..i64::MAX))
+            .collect();
+        write_numbers(&input, values.iter().copied()).unwrap();
+
+        let config = small_config(&dir, 300);
+        let stats = external_sort(&input, &output, &config).unwrap();
+
+        assert_eq!(stats.values, 10_000);
+        assert_eq!(stats.runs, 10_000usize.div_ceil(300));
+        assert_eq!(stats.merge_passes, 6);
+
+        let mut expected = values;
+        expected.sort();
+        assert_eq!(read_numbers(&output), expected);
+
+        // nothing left behind in the temp dir
+        assert_eq!(fs::read_dir(&config.temp_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_merges_in_passes_past_the_fan_in() {
+        let dir = scratch_dir();
+        let input = dir.path.join("input.txt");
+        let output = dir.path.join("output.txt");
+
+        let values = Rng::new(470).vec_i32(10_000, -500..500);
+        write_numbers(&input, values.iter().map(|&value| value as i64)).unwrap();
+
+        // room for 3 read buffers, so the 4 runs of 3072 values need two passes
+        let config = ExternalSortConfig {
+            memory_budget: 3 * RUN_BUFFER,
+            temp_dir: dir.path.join("tmp"),
+        };
+        assert_eq!(fan_in(config.memory_budget), 3);
+
+        let stats = external_sort(&input, &output, &config).unwrap();
+        assert_eq!((stats.runs, stats.merge_passes), (4, 2));
+
+        let mut expected: Vec<i64> = values.iter().map(|&value| value as i64).collect();
+        expected.sort();
+        assert_eq!(read_numbers(&output), expected);
+        assert_eq!(fs::read_dir(&config.temp_dir).unwrap().count(), 0);
+
+        // the tiny budget above merges two at a time, 34 runs in 6 passes
+        assert_eq!(fan_in(300 * size_of::<i64>()), 2);
+        assert_eq!(fan_in(usize::MAX), MAX_FAN_IN);
+    }
+
+    #[test]
+    fn test_duplicates_and_a_single_value_budget() {
+        let dir = scratch_dir();
+        let input = dir.path.join("input.txt");
+        let output = dir.path.join("output.txt");
+
+        let values = [3, -1, 3, 3, 0, -1, 7];
+        write_numbers(&input, values.into_iter()).unwrap();
+
+        // a budget too small for even one value still makes progress
+        let mut config = small_config(&dir, 0);
+        config.memory_budget = 1;
+        let stats = external_sort(&input, &output, &config).unwrap();
+
+        assert_eq!(stats.runs, values.len());
+        assert_eq!(read_numbers(&output), vec![-1, -1, 0, 3, 3, 3, 7]);
+    }
+
+    #[test]
+    fn test_empty_and_messy_input() {
+        let dir = scratch_dir();
+        let input = dir.path.join("input.txt");
+        let output = dir.path.join("output.txt");
+        let config = small_config(&dir, 2);
+
+        fs::write(&input, "").unwrap();
+        let stats = external_sort(&input, &output, &config).unwrap();
+        assert_eq!(
+            stats,
+            ExternalSortStats {
+                values: 0,
+                runs: 0,
+                merge_passes: 1,
+            }
+        );
+        assert_eq!(fs::read_to_string(&output).unwrap(), "");
+
+        // blank lines, padding, windows line endings and no final newline
+        fs::write(&input, "5\n\n  -2 \r\n9\n\n1").unwrap();
+        external_sort(&input, &output, &config).unwrap();
+        assert_eq!(fs::read_to_string(&output).unwrap(), "-2\n1\n5\n9\n");
+    }
+
+    #[test]
+    fn test_invalid_input_is_an_error_and_cleans_up() {
+        let dir = scratch_dir();
+        let input = dir.path.join("input.txt");
+        let output = dir.path.join("output.txt");
+        let config = small_config(&dir, 2);
+
+        fs::write(&input, "1\n2\n3\n4\nfive\n6\n").unwrap();
+        let err = external_sort(&input, &output, &config).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("line 5"), "{err}");
+        assert_eq!(fs::read_dir(&config.temp_dir).unwrap().count(), 0);
+
+        let missing = dir.path.join("missing.txt");
+        let err = external_sort(&missing, &output, &config).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
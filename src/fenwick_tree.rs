@@ -0,0 +1,152 @@
+use std::ops::Range;
+
+/// binary indexed tree for prefix sums with point updates, both O(log n)
+#[derive(Debug, Clone)]
+pub struct FenwickTree {
+    // 1-indexed internally, `tree[i]` holds the sum of the `i & -i` values ending at `i`
+    tree: Vec<i64>,
+}
+
+impl FenwickTree {
+    pub fn new(len: usize) -> Self {
+        FenwickTree {
+            tree: vec![0; len + 1],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// adds `delta` to the value at `idx`
+    pub fn add(&mut self, idx: usize, delta: i64) {
+        assert!(idx < self.len(), "index out of bounds");
+
+        let mut i = idx + 1;
+
+        while i < self.tree.len() {
+            self.tree[i] += delta;
+            i += Self::lowest_bit(i);
+        }
+    }
+
+    /// sum of the first `end` values, `[0, end)`
+    pub fn prefix_sum(&self, end: usize) -> i64 {
+        assert!(end <= self.len(), "index out of bounds");
+
+        let mut sum = 0;
+        let mut i = end;
+
+        while i > 0 {
+            sum += self.tree[i];
+            i -= Self::lowest_bit(i);
+        }
+
+        sum
+    }
+
+    pub fn range_sum(&self, range: Range<usize>) -> i64 {
+        if range.start >= range.end {
+            return 0;
+        }
+
+        self.prefix_sum(range.end) - self.prefix_sum(range.start)
+    }
+
+    pub fn get(&self, idx: usize) -> i64 {
+        self.range_sum(idx..idx + 1)
+    }
+
+    pub fn set(&mut self, idx: usize, value: i64) {
+        let current = self.get(idx);
+        self.add(idx, value - current);
+    }
+
+    fn lowest_bit(i: usize) -> usize {
+        i & i.wrapping_neg()
+    }
+}
+
+impl From<&[i64]> for FenwickTree {
+    /// builds in O(n) by pushing each partial sum into its parent once
+    fn from(values: &[i64]) -> Self {
+        let mut tree = vec![0; values.len() + 1];
+        tree[1..].copy_from_slice(values);
+
+        for i in 1..tree.len() {
+            let parent = i + Self::lowest_bit(i);
+
+            if parent < tree.len() {
+                tree[parent] += tree[i];
+            }
+        }
+
+        FenwickTree { tree }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FenwickTree;
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_prefix_sums() {
+        let tree = FenwickTree::from(&[3, 2, -1, 6, 5, 4, -3, 3][..]);
+
+        assert_eq!(tree.len(), 8);
+        assert_eq!(tree.prefix_sum(0), 0);
+        assert_eq!(tree.prefix_sum(1), 3);
+        assert_eq!(tree.prefix_sum(4), 10);
+        assert_eq!(tree.prefix_sum(8), 19);
+        assert_eq!(tree.range_sum(2..5), 10);
+        assert_eq!(tree.range_sum(3..3), 0);
+        assert_eq!(tree.get(6), -3);
+    }
+
+    #[test]
+    fn test_new_is_all_zero() {
+        let mut tree = FenwickTree::new(5);
+
+        assert_eq!(tree.prefix_sum(5), 0);
+
+        tree.add(2, 7);
+        tree.set(4, 1);
+        assert_eq!(tree.range_sum(0..5), 8);
+        assert_eq!(tree.get(2), 7);
+        assert!(FenwickTree::new(0).is_empty());
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        let mut rng = Rng::new(30);
+
+        for len in 0..60 {
+            let mut values: Vec<i64> = (0..len).map(|_| rng.range_i64(-1000..1000)).collect();
+            let mut tree = FenwickTree::from(&values[..]);
+
+            for _ in 0..100 {
+                if len > 0 {
+                    let idx = rng.range_usize(0..len);
+                    let delta = rng.range_i64(-100..100);
+                    values[idx] += delta;
+                    tree.add(idx, delta);
+                }
+
+                let a = rng.range_usize(0..len + 1);
+                let b = rng.range_usize(0..len + 1);
+                let range = a.min(b)..a.max(b);
+
+                assert_eq!(tree.prefix_sum(b), values[..b].iter().sum::<i64>());
+                assert_eq!(
+                    tree.range_sum(range.clone()),
+                    values[range].iter().sum::<i64>()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::cmp::Ordering;
+
+use crate::min_heap::sift_down;
+use crate::tracer::{NoopTracer, TraceEvent, Tracer};
+
+pub fn heap_sort<T: Ord>(arr: &mut [T]) {
+    heap_sort_by(arr, T::cmp);
+}
+
+pub fn heap_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    heap_sort_by_traced(arr, compare, &mut NoopTracer);
+}
+
+/// in place heapsort, O(n log n) on any input with O(1) extra space, not stable
+///
+/// builds a max heap bottom up with `MinHeap`'s sift-down run on the reversed
+/// ordering, then swaps the max behind the shrinking heap until it is empty
+pub fn heap_sort_by_traced<T, F>(arr: &mut [T], mut compare: F, tracer: &mut impl Tracer)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    let mut max_first = |a: &T, b: &T| compare(b, a);
+
+    for idx in (0..len / 2).rev() {
+        sift_down(arr, idx, &mut max_first, tracer);
+    }
+
+    for end in (1..len).rev() {
+        tracer.trace(TraceEvent::Swap(0, end));
+        arr.swap(0, end);
+        sift_down(&mut arr[..end], 0, &mut max_first, tracer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::sort_check::is_sorted;
+    use crate::tracer::{CountingTracer, RecordingTracer};
+
+    #[test]
+    fn test_matches_std() {
+        let mut rng = Rng::new(44);
+
+        for len in 0..200 {
+            for input in [
+                rng.vec_i32(len, i32::MIN..i32::MAX),
+                rng.vec_i32(len, 0..5),
+                (0..len as i32).collect(),
+                (0..len as i32).rev().collect(),
+            ] {
+                let mut arr = input.clone();
+                let mut expected = input;
+                expected.sort();
+
+                heap_sort(&mut arr);
+                assert_eq!(arr, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sorts_by_comparator_and_key() {
+        let mut words = ["pear", "fig", "banana", "kiwi", "apple", "date"];
+
+        heap_sort_by(&mut words, |a, b| a.len().cmp(&b.len()).then(a.cmp(b)));
+        assert_eq!(words, ["fig", "date", "kiwi", "pear", "apple", "banana"]);
+
+        heap_sort_by(&mut words, |a, b| b.cmp(a));
+        assert_eq!(words, ["pear", "kiwi", "fig", "date", "banana", "apple"]);
+    }
+
+    #[test]
+    fn test_n_log_n_on_every_shape() {
+        let len: usize = 4096;
+        let bound = 2 * len * len.ilog2() as usize;
+        let mut rng = Rng::new(440);
+
+        for input in [
+            (0..len as i32).collect(),
+            (0..len as i32).rev().collect(),
+            vec![1; len],
+            rng.vec_i32(len, 0..i32::MAX),
+        ] {
+            let mut arr: Vec<i32> = input;
+            let mut counter = CountingTracer::default();
+
+            heap_sort_by_traced(&mut arr, i32::cmp, &mut counter);
+            assert!(is_sorted(&arr));
+            assert!(counter.compares <= bound, "{}", counter.compares);
+        }
+    }
+
+    #[test]
+    fn test_traced_swaps_replay_to_sorted() {
+        let original = Rng::new(441).vec_i32(300, 0..50);
+        let mut arr = original.clone();
+        let mut recorder = RecordingTracer::default();
+
+        heap_sort_by_traced(&mut arr, i32::cmp, &mut recorder);
+
+        let mut replay = original;
+        for event in &recorder.events {
+            if let TraceEvent::Swap(a, b) = *event {
+                replay.swap(a, b);
+            }
+        }
+        assert_eq!(replay, arr);
+    }
+}
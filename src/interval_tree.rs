@@ -0,0 +1,354 @@
+/// closed interval `[low, high]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Interval {
+    pub low: i32,
+    pub high: i32,
+}
+
+impl Interval {
+    pub fn new(low: i32, high: i32) -> Self {
+        assert!(low <= high, "interval low must not exceed high");
+        Interval { low, high }
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.low <= other.high && other.low <= self.high
+    }
+}
+
+#[derive(Debug)]
+struct IntervalNode {
+    interval: Interval,
+    // largest `high` anywhere in this subtree
+    max: i32,
+    left: Option<Box<IntervalNode>>,
+    right: Option<Box<IntervalNode>>,
+}
+
+impl IntervalNode {
+    fn new(interval: Interval) -> Self {
+        IntervalNode {
+            interval,
+            max: interval.high,
+            left: None,
+            right: None,
+        }
+    }
+
+    /// recomputes `max` from the node and its direct children
+    fn update_max(&mut self) {
+        self.max = self.interval.high;
+
+        if let Some(left) = &self.left {
+            self.max = self.max.max(left.max);
+        }
+
+        if let Some(right) = &self.right {
+            self.max = self.max.max(right.max);
+        }
+    }
+}
+
+/// BST of intervals ordered by `(low, high)`, where every node is augmented
+/// with the max endpoint of its subtree so overlap queries can skip whole branches
+#[derive(Debug, Default)]
+pub struct IntervalTree {
+    length: usize,
+    root: Option<Box<IntervalNode>>,
+}
+
+impl IntervalTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// inserts an interval, equal intervals are kept as separate entries
+    pub fn insert(&mut self, interval: Interval) {
+        Self::insert_into(&mut self.root, interval);
+        self.length += 1;
+    }
+
+    fn insert_into(node: &mut Option<Box<IntervalNode>>, interval: Interval) {
+        match node {
+            None => *node = Some(Box::new(IntervalNode::new(interval))),
+            Some(n) => {
+                if interval < n.interval {
+                    Self::insert_into(&mut n.left, interval);
+                } else {
+                    Self::insert_into(&mut n.right, interval);
+                }
+
+                n.max = n.max.max(interval.high);
+            }
+        }
+    }
+
+    /// deletes one occurrence of the interval
+    /// returns true if the interval was found and deleted
+    pub fn delete(&mut self, interval: &Interval) -> bool {
+        let deleted = Self::delete_in_place(&mut self.root, interval);
+
+        if deleted {
+            self.length -= 1;
+        }
+
+        deleted
+    }
+
+    fn delete_in_place(node: &mut Option<Box<IntervalNode>>, interval: &Interval) -> bool {
+        let Some(n) = node else {
+            return false;
+        };
+
+        let deleted = match interval.cmp(&n.interval) {
+            std::cmp::Ordering::Less => Self::delete_in_place(&mut n.left, interval),
+            std::cmp::Ordering::Greater => Self::delete_in_place(&mut n.right, interval),
+            std::cmp::Ordering::Equal => {
+                match (n.left.take(), n.right.take()) {
+                    // Leaf node
+                    (None, None) => *node = None,
+                    // Only one child
+                    (Some(child), None) | (None, Some(child)) => *node = Some(child),
+                    // Two children, replace with the in-order successor
+                    (Some(left), Some(right)) => {
+                        let mut right = Some(right);
+                        n.interval = Self::extract_min(&mut right);
+                        n.left = Some(left);
+                        n.right = right;
+                        n.update_max();
+                    }
+                }
+
+                return true;
+            }
+        };
+
+        if deleted {
+            n.update_max();
+        }
+
+        deleted
+    }
+
+    /// removes the leftmost node of a non-empty subtree and returns its interval
+    fn extract_min(node: &mut Option<Box<IntervalNode>>) -> Interval {
+        let n = node.as_mut().unwrap();
+
+        if n.left.is_some() {
+            let min = Self::extract_min(&mut n.left);
+            n.update_max();
+            min
+        } else {
+            let min = n.interval;
+            *node = n.right.take();
+            min
+        }
+    }
+
+    /// returns some stored interval overlapping `query`, if any
+    pub fn find_any_overlap(&self, query: &Interval) -> Option<Interval> {
+        let mut curr = self.root.as_deref();
+
+        while let Some(node) = curr {
+            if node.interval.overlaps(query) {
+                return Some(node.interval);
+            }
+
+            // once the left subtree reaches past `query.low`, the right subtree
+            // can only hold an overlap if the left one does too
+            curr = match node.left.as_deref() {
+                Some(left) if left.max >= query.low => Some(left),
+                _ => node.right.as_deref(),
+            };
+        }
+
+        None
+    }
+
+    /// every stored interval overlapping `query`, ordered by `(low, high)`
+    pub fn find_all_overlaps(&self, query: &Interval) -> Vec<Interval> {
+        let mut found = Vec::new();
+        Self::collect_overlaps(self.root.as_deref(), query, &mut found);
+        found
+    }
+
+    fn collect_overlaps(node: Option<&IntervalNode>, query: &Interval, found: &mut Vec<Interval>) {
+        let Some(node) = node else {
+            return;
+        };
+
+        // nothing in this subtree reaches far enough right
+        if node.max < query.low {
+            return;
+        }
+
+        Self::collect_overlaps(node.left.as_deref(), query, found);
+
+        if node.interval.overlaps(query) {
+            found.push(node.interval);
+        }
+
+        // the right subtree only starts later, so skip it once we're past the query
+        if node.interval.low <= query.high {
+            Self::collect_overlaps(node.right.as_deref(), query, found);
+        }
+    }
+
+    #[cfg(test)]
+    fn is_valid(&self) -> bool {
+        fn check(
+            node: Option<&IntervalNode>,
+            lower: Option<Interval>,
+            upper: Option<Interval>,
+        ) -> bool {
+            let Some(n) = node else {
+                return true;
+            };
+
+            let expected_max = [n.left.as_deref(), n.right.as_deref()]
+                .into_iter()
+                .flatten()
+                .map(|child| child.max)
+                .fold(n.interval.high, i32::max);
+
+            n.max == expected_max
+                && lower.is_none_or(|lo| lo <= n.interval)
+                && upper.is_none_or(|hi| n.interval < hi)
+                && check(n.left.as_deref(), lower, Some(n.interval))
+                && check(n.right.as_deref(), Some(n.interval), upper)
+        }
+
+        check(self.root.as_deref(), None, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    fn random_interval(rng: &mut Rng) -> Interval {
+        let low = rng.range_i32(0..1000);
+        let len = rng.range_i32(0..50);
+        Interval::new(low, low + len)
+    }
+
+    fn linear_scan(intervals: &[Interval], query: &Interval) -> Vec<Interval> {
+        let mut found: Vec<Interval> = intervals
+            .iter()
+            .filter(|interval| interval.overlaps(query))
+            .copied()
+            .collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn test_overlap() {
+        let a = Interval::new(5, 10);
+
+        assert!(a.overlaps(&Interval::new(10, 12)), "touching ends overlap");
+        assert!(a.overlaps(&Interval::new(0, 5)));
+        assert!(a.overlaps(&Interval::new(6, 7)));
+        assert!(a.overlaps(&Interval::new(0, 20)));
+        assert!(!a.overlaps(&Interval::new(11, 12)));
+        assert!(!a.overlaps(&Interval::new(0, 4)));
+    }
+
+    #[test]
+    fn test_meeting_schedule() {
+        let mut tree = IntervalTree::new();
+
+        for (low, high) in [(15, 20), (10, 30), (17, 19), (5, 20), (12, 15), (30, 40)] {
+            tree.insert(Interval::new(low, high));
+        }
+
+        assert_eq!(tree.len(), 6);
+        assert!(tree.is_valid());
+
+        assert_eq!(
+            tree.find_all_overlaps(&Interval::new(6, 11)),
+            vec![Interval::new(5, 20), Interval::new(10, 30)]
+        );
+        assert!(tree.find_any_overlap(&Interval::new(41, 50)).is_none());
+        assert!(tree.find_any_overlap(&Interval::new(0, 4)).is_none());
+        assert_eq!(
+            tree.find_any_overlap(&Interval::new(35, 36)),
+            Some(Interval::new(30, 40))
+        );
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut tree = IntervalTree::new();
+
+        for (low, high) in [(15, 20), (10, 30), (17, 19), (5, 20), (12, 15), (30, 40)] {
+            tree.insert(Interval::new(low, high));
+        }
+
+        assert!(tree.delete(&Interval::new(15, 20)), "delete the root");
+        assert!(tree.is_valid());
+        assert!(tree.delete(&Interval::new(30, 40)), "delete the max holder");
+        assert!(tree.is_valid());
+        assert!(!tree.delete(&Interval::new(30, 40)));
+        assert!(!tree.delete(&Interval::new(1, 2)));
+
+        assert_eq!(tree.len(), 4);
+        assert!(tree.find_any_overlap(&Interval::new(35, 36)).is_none());
+    }
+
+    #[test]
+    fn test_duplicate_intervals() {
+        let mut tree = IntervalTree::new();
+        let interval = Interval::new(1, 5);
+
+        tree.insert(interval);
+        tree.insert(interval);
+
+        assert_eq!(tree.find_all_overlaps(&interval), vec![interval, interval]);
+        assert!(tree.delete(&interval));
+        assert_eq!(tree.find_all_overlaps(&interval), vec![interval]);
+        assert!(tree.delete(&interval));
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_against_linear_scan() {
+        let mut rng = Rng::new(31);
+        let mut tree = IntervalTree::new();
+        let mut reference: Vec<Interval> = Vec::new();
+
+        for _ in 0..2000 {
+            if !reference.is_empty() && rng.range_usize(0..3) == 0 {
+                let idx = rng.range_usize(0..reference.len());
+                let interval = reference.swap_remove(idx);
+                assert!(tree.delete(&interval));
+            } else {
+                let interval = random_interval(&mut rng);
+                tree.insert(interval);
+                reference.push(interval);
+            }
+
+            let query = random_interval(&mut rng);
+            let expected = linear_scan(&reference, &query);
+
+            assert_eq!(tree.find_all_overlaps(&query), expected);
+
+            match tree.find_any_overlap(&query) {
+                Some(found) => assert!(expected.contains(&found)),
+                None => assert!(expected.is_empty()),
+            }
+        }
+
+        assert_eq!(tree.len(), reference.len());
+        assert!(tree.is_valid());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::tracer::{NoopTracer, TraceEvent, Tracer};
+
 fn linear_search(haystack: &[u32; 5], needle: u32) -> bool {
     for v in haystack.iter() {
         if *v == needle {
@@ -8,9 +10,150 @@ fn linear_search(haystack: &[u32; 5], needle: u32) -> bool {
     false
 }
 
+/// index of the first item yielded by any iterator that matches `pred`
+pub fn position<I, P>(items: I, mut pred: P) -> Option<usize>
+where
+    I: IntoIterator,
+    P: FnMut(&I::Item) -> bool,
+{
+    for (idx, item) in items.into_iter().enumerate() {
+        if pred(&item) {
+            return Some(idx);
+        }
+    }
+
+    None
+}
+
+/// index of the first element equal to the needle
+pub fn find_first<T: PartialEq>(haystack: &[T], needle: &T) -> Option<usize> {
+    find_first_by(haystack, |v| v == needle)
+}
+
+pub fn find_first_by<T, P>(haystack: &[T], pred: P) -> Option<usize>
+where
+    P: FnMut(&T) -> bool,
+{
+    find_first_by_traced(haystack, pred, &mut NoopTracer)
+}
+
+/// same as `find_first_by`, reporting every element it reads as a probe
+pub fn find_first_by_traced<T, P>(
+    haystack: &[T],
+    mut pred: P,
+    tracer: &mut impl Tracer,
+) -> Option<usize>
+where
+    P: FnMut(&T) -> bool,
+{
+    for (idx, v) in haystack.iter().enumerate() {
+        tracer.trace(TraceEvent::Probe(idx));
+        if pred(v) {
+            return Some(idx);
+        }
+    }
+
+    None
+}
+
+/// index of the last element equal to the needle
+pub fn find_last<T: PartialEq>(haystack: &[T], needle: &T) -> Option<usize> {
+    find_last_by(haystack, |v| v == needle)
+}
+
+pub fn find_last_by<T, P>(haystack: &[T], pred: P) -> Option<usize>
+where
+    P: FnMut(&T) -> bool,
+{
+    find_last_by_traced(haystack, pred, &mut NoopTracer)
+}
+
+/// same as `find_last_by`, reporting every element it reads as a probe
+pub fn find_last_by_traced<T, P>(
+    haystack: &[T],
+    mut pred: P,
+    tracer: &mut impl Tracer,
+) -> Option<usize>
+where
+    P: FnMut(&T) -> bool,
+{
+    for (idx, v) in haystack.iter().enumerate().rev() {
+        tracer.trace(TraceEvent::Probe(idx));
+        if pred(v) {
+            return Some(idx);
+        }
+    }
+
+    None
+}
+
+/// indices of every element equal to the needle, in ascending order
+pub fn find_all<T: PartialEq>(haystack: &[T], needle: &T) -> Vec<usize> {
+    find_all_by(haystack, |v| v == needle)
+}
+
+pub fn find_all_by<T, P>(haystack: &[T], pred: P) -> Vec<usize>
+where
+    P: FnMut(&T) -> bool,
+{
+    find_all_by_traced(haystack, pred, &mut NoopTracer)
+}
+
+/// same as `find_all_by`, reporting every element it reads as a probe
+pub fn find_all_by_traced<T, P>(haystack: &[T], mut pred: P, tracer: &mut impl Tracer) -> Vec<usize>
+where
+    P: FnMut(&T) -> bool,
+{
+    let mut found = Vec::new();
+
+    for (idx, v) in haystack.iter().enumerate() {
+        tracer.trace(TraceEvent::Probe(idx));
+        if pred(v) {
+            found.push(idx);
+        }
+    }
+
+    found
+}
+
+/// sentinel linear search: the needle is planted in the last slot so the loop
+/// only needs to compare values and never checks whether it ran off the end
+/// the slice is restored before returning
+pub fn sentinel_search<T: PartialEq + Clone>(haystack: &mut [T], needle: &T) -> Option<usize> {
+    sentinel_search_traced(haystack, needle, &mut NoopTracer)
+}
+
+/// same as `sentinel_search`, reporting every element it reads as a probe
+pub fn sentinel_search_traced<T: PartialEq + Clone>(
+    haystack: &mut [T],
+    needle: &T,
+    tracer: &mut impl Tracer,
+) -> Option<usize> {
+    let last = haystack.len().checked_sub(1)?;
+    let original = std::mem::replace(&mut haystack[last], needle.clone());
+
+    let mut idx = 0;
+    tracer.trace(TraceEvent::Probe(idx));
+    while haystack[idx] != *needle {
+        idx += 1;
+        tracer.trace(TraceEvent::Probe(idx));
+    }
+
+    let found_last = original == *needle;
+    haystack[last] = original;
+
+    if idx < last || found_last {
+        Some(idx)
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::linear_search;
+    use super::*;
+    use crate::rng::Rng;
+    use crate::tracer::{CountingTracer, RecordingTracer};
 
     #[test]
     fn it_can_perform_linear_search() {
@@ -29,4 +172,155 @@ mod tests {
 
         assert!(!result);
     }
+
+    #[test]
+    fn it_finds_first_and_last_index() {
+        let a = [4, 2, 7, 2, 9, 2];
+
+        assert_eq!(find_first(&a, &2), Some(1));
+        assert_eq!(find_last(&a, &2), Some(5));
+        assert_eq!(find_first(&a, &4), Some(0));
+        assert_eq!(find_last(&a, &9), Some(4));
+        assert_eq!(find_first(&a, &8), None);
+        assert_eq!(find_last(&a, &8), None);
+        assert_eq!(find_first(&[] as &[i32], &1), None);
+    }
+
+    #[test]
+    fn it_finds_by_predicate() {
+        let words = ["apple", "kiwi", "banana", "fig", "cherry"];
+
+        assert_eq!(find_first_by(&words, |w| w.len() > 5), Some(2));
+        assert_eq!(find_last_by(&words, |w| w.len() > 5), Some(4));
+        assert_eq!(find_all_by(&words, |w| w.len() <= 4), vec![1, 3]);
+        assert_eq!(find_first_by(&words, |w| w.is_empty()), None);
+    }
+
+    #[test]
+    fn it_finds_all_indices() {
+        let a = [1, 3, 1, 1, 5];
+
+        assert_eq!(find_all(&a, &1), vec![0, 2, 3]);
+        assert_eq!(find_all(&a, &6), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn it_searches_any_iterator() {
+        let odd_squares = (1..).map(|x| x * x).filter(|x| x % 2 == 1);
+
+        assert_eq!(position(odd_squares, |x| *x > 50), Some(4));
+        assert_eq!(position("hello".chars(), |c| *c == 'l'), Some(2));
+        assert_eq!(position(Vec::<i32>::new(), |_| true), None);
+    }
+
+    #[test]
+    fn it_finds_with_sentinel_and_restores_the_slice() {
+        let mut a = [5, 8, 1, 8, 3];
+
+        assert_eq!(sentinel_search(&mut a, &8), Some(1));
+        assert_eq!(sentinel_search(&mut a, &3), Some(4));
+        assert_eq!(sentinel_search(&mut a, &7), None);
+        assert_eq!(a, [5, 8, 1, 8, 3]);
+
+        let mut empty: [i32; 0] = [];
+        assert_eq!(sentinel_search(&mut empty, &1), None);
+    }
+
+    #[test]
+    fn it_traces_every_element_it_reads() {
+        let a = [4, 2, 7, 2, 9];
+        let probes = |search: &mut dyn FnMut(&mut RecordingTracer)| {
+            let mut tracer = RecordingTracer::default();
+            search(&mut tracer);
+            tracer
+                .events
+                .into_iter()
+                .map(|event| match event {
+                    TraceEvent::Probe(idx) => idx,
+                    other => panic!("a search only probes, got {other:?}"),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            probes(&mut |t| assert_eq!(find_first_by_traced(&a, |v| *v == 2, t), Some(1))),
+            [0, 1]
+        );
+        assert_eq!(
+            probes(&mut |t| assert_eq!(find_last_by_traced(&a, |v| *v == 2, t), Some(3))),
+            [4, 3]
+        );
+        assert_eq!(
+            probes(&mut |t| assert_eq!(find_all_by_traced(&a, |v| *v == 2, t), [1, 3])),
+            [0, 1, 2, 3, 4]
+        );
+
+        // a miss reads the planted sentinel too
+        let mut b = a;
+        let mut tracer = CountingTracer::default();
+        assert_eq!(sentinel_search_traced(&mut b, &8, &mut tracer), None);
+        assert_eq!(tracer.probes, a.len());
+    }
+
+    #[test]
+    fn it_matches_std_on_random_inputs() {
+        let mut rng = Rng::new(33);
+
+        for len in 0..60 {
+            let mut a = rng.vec_i32(len, 0..10);
+            let original = a.clone();
+
+            for needle in -1..11 {
+                let expected_first = a.iter().position(|v| *v == needle);
+
+                assert_eq!(find_first(&a, &needle), expected_first);
+                assert_eq!(find_last(&a, &needle), a.iter().rposition(|v| *v == needle));
+                assert_eq!(sentinel_search(&mut a, &needle), expected_first);
+                assert_eq!(
+                    find_all(&a, &needle).len(),
+                    a.iter().filter(|v| **v == needle).count()
+                );
+            }
+
+            assert_eq!(a, original);
+        }
+    }
+
+    /// run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_against_naive_loop() {
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        let mut rng = Rng::new(1);
+        let mut haystack = rng.vec_i32(1_000_000, 0..i32::MAX);
+        let needle = -1;
+        let rounds = 50;
+
+        let start = Instant::now();
+        for _ in 0..rounds {
+            let mut found = None;
+            for (idx, v) in black_box(&haystack).iter().enumerate() {
+                if *v == needle {
+                    found = Some(idx);
+                    break;
+                }
+            }
+            black_box(found);
+        }
+        println!("naive loop:      {:?}", start.elapsed() / rounds);
+
+        let start = Instant::now();
+        for _ in 0..rounds {
+            black_box(find_first(black_box(&haystack), &needle));
+        }
+        println!("find_first:      {:?}", start.elapsed() / rounds);
+
+        let start = Instant::now();
+        for _ in 0..rounds {
+            black_box(sentinel_search(black_box(&mut haystack), &needle));
+        }
+        println!("sentinel_search: {:?}", start.elapsed() / rounds);
+    }
 }
@@ -1,19 +1,79 @@
+// the katas are exercised through their tests, main doesn't use any of them
+#[cfg(test)]
+mod b_tree;
+#[cfg(test)]
+mod balanced_bst;
+#[cfg(test)]
 mod binary_node;
+#[cfg(test)]
 mod binary_search_algo;
+#[cfg(test)]
 mod bt_bfs;
+#[cfg(test)]
 mod bt_in_order;
+#[cfg(test)]
 mod bt_post_order;
+#[cfg(test)]
 mod bt_pre_order;
+#[cfg(test)]
 mod bubble_sort;
+#[cfg(test)]
 mod compare_binary_trees;
+#[cfg(test)]
 mod dfs_on_bst;
+#[cfg(test)]
+mod distribution_sort;
+#[cfg(test)]
 mod doubly_linked_list;
+#[cfg(test)]
+mod elementary_sort;
+#[cfg(test)]
+mod external_sort;
+#[cfg(test)]
+mod fenwick_tree;
+#[cfg(test)]
+mod heap_sort;
+#[cfg(test)]
+mod interval_tree;
+#[cfg(test)]
 mod linear_search_list;
+#[cfg(test)]
 mod maze_solver;
+#[cfg(test)]
+mod merge_sort;
+#[cfg(test)]
 mod min_heap;
+#[cfg(test)]
+mod parallel_sort;
+#[cfg(test)]
 mod queue;
+#[cfg(test)]
 mod quicksort;
+#[cfg(test)]
+mod rng;
+#[cfg(test)]
+mod search_strategies;
+#[cfg(test)]
+mod segment_tree;
+#[cfg(test)]
+mod selection;
+#[cfg(test)]
+mod sort_check;
+#[cfg(test)]
+mod sort_replay;
+#[cfg(test)]
+mod sorting;
+#[cfg(test)]
 mod stack;
+#[cfg(test)]
+mod tim_sort;
+#[cfg(test)]
+mod tracer;
+#[cfg(test)]
+mod tree_gen;
+#[cfg(test)]
+mod trie;
+#[cfg(test)]
 mod two_crystal_ball_problem;
 
 fn main() {
@@ -0,0 +1,384 @@
+use std::cmp::Ordering;
+
+use crate::tracer::{NoopTracer, TraceEvent, WriteTracer};
+
+/// which merge sort to run, all three are stable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeVariant {
+    /// split in halves recursively, merge on the way back up
+    TopDown,
+    /// merge runs of 1, 2, 4, ... elements with no recursion at all
+    BottomUp,
+    /// merge the runs already present in the input, O(n) on sorted data
+    Natural,
+}
+
+pub fn merge_sort<T: Ord + Clone>(arr: &mut [T], variant: MergeVariant) {
+    merge_sort_by(arr, variant, T::cmp);
+}
+
+pub fn merge_sort_by<T, F>(arr: &mut [T], variant: MergeVariant, compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    merge_sort_with_buffer(arr, variant, &mut Vec::new(), compare);
+}
+
+/// merge sort reporting its comparisons, the swaps that reverse descending
+/// runs, and every value a merge writes back
+///
+/// a merge compares against the copy of the shorter run in the scratch
+/// buffer, those compares name the index that element started out at
+pub fn merge_sort_by_traced<T, F>(
+    arr: &mut [T],
+    variant: MergeVariant,
+    mut compare: F,
+    tracer: &mut impl WriteTracer<T>,
+) where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    Merger {
+        scratch: &mut Vec::new(),
+        compare: &mut compare,
+        tracer,
+    }
+    .sort(arr, variant);
+}
+
+/// merge sort that takes its scratch space from `scratch`, so sorting many
+/// slices one after another allocates only once
+///
+/// `scratch` is cleared before use and grows to at most half of `arr`
+pub fn merge_sort_with_buffer<T, F>(
+    arr: &mut [T],
+    variant: MergeVariant,
+    scratch: &mut Vec<T>,
+    mut compare: F,
+) where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    Merger {
+        scratch,
+        compare: &mut compare,
+        tracer: &mut NoopTracer,
+    }
+    .sort(arr, variant);
+}
+
+/// merges the sorted runs `arr[..mid]` and `arr[mid..]` in place
+///
+/// only the shorter run is copied out to `scratch`, merging from the front
+/// when it is the left one and from the back when it is the right one, so the
+/// output never overwrites an element that hasn't been read yet
+///
+/// ties always go to the left run, which is what keeps every merge sort built
+/// on this stable
+pub(crate) fn merge<T, F>(arr: &mut [T], mid: usize, scratch: &mut Vec<T>, compare: &mut F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    Merger {
+        scratch,
+        compare,
+        tracer: &mut NoopTracer,
+    }
+    .merge(arr, 0, mid, len);
+}
+
+/// the state one merge sort threads through its merges, every range is a
+/// half-open `lo..hi` of the whole slice so traced indices need no shifting
+struct Merger<'a, T, F, R> {
+    scratch: &'a mut Vec<T>,
+    compare: &'a mut F,
+    tracer: &'a mut R,
+}
+
+impl<T, F, R> Merger<'_, T, F, R>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+    R: WriteTracer<T>,
+{
+    fn sort(&mut self, arr: &mut [T], variant: MergeVariant) {
+        match variant {
+            MergeVariant::TopDown => self.top_down(arr, 0, arr.len()),
+            MergeVariant::BottomUp => self.bottom_up(arr),
+            MergeVariant::Natural => self.natural(arr),
+        }
+    }
+
+    fn top_down(&mut self, arr: &mut [T], lo: usize, hi: usize) {
+        if hi - lo <= 1 {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        self.top_down(arr, lo, mid);
+        self.top_down(arr, mid, hi);
+        self.merge(arr, lo, mid, hi);
+    }
+
+    fn bottom_up(&mut self, arr: &mut [T]) {
+        let len = arr.len();
+        let mut width = 1;
+
+        while width < len {
+            for start in (0..len).step_by(2 * width) {
+                let mid = (start + width).min(len);
+                let end = (start + 2 * width).min(len);
+
+                if mid < end {
+                    self.merge(arr, start, mid, end);
+                }
+            }
+
+            width *= 2;
+        }
+    }
+
+    fn natural(&mut self, arr: &mut [T]) {
+        // where each run ends, the last one always at `arr.len()`
+        let mut run_ends = self.find_runs(arr);
+
+        while run_ends.len() > 1 {
+            let mut merged_ends = Vec::with_capacity(run_ends.len().div_ceil(2));
+            let mut start = 0;
+
+            for pair in run_ends.chunks(2) {
+                if let [mid, end] = *pair {
+                    self.merge(arr, start, mid, end);
+                }
+
+                let end = *pair.last().unwrap();
+                merged_ends.push(end);
+                start = end;
+            }
+
+            run_ends = merged_ends;
+        }
+    }
+
+    // splits `arr` into maximal non-descending runs, reversing strictly descending
+    // ones in place (strict, so reversing them can't reorder equal elements)
+    fn find_runs(&mut self, arr: &mut [T]) -> Vec<usize> {
+        let mut run_ends = Vec::new();
+        let mut start = 0;
+
+        while start < arr.len() {
+            let mut end = start + 1;
+
+            if end < arr.len() {
+                // the first pair decides which way the run goes
+                let descending = self.is_less(arr, end, start);
+                end += 1;
+
+                while end < arr.len() && self.is_less(arr, end, end - 1) == descending {
+                    end += 1;
+                }
+
+                if descending {
+                    for i in 0..(end - start) / 2 {
+                        self.tracer.trace(TraceEvent::Swap(start + i, end - 1 - i));
+                        arr.swap(start + i, end - 1 - i);
+                    }
+                }
+            }
+
+            run_ends.push(end);
+            start = end;
+        }
+
+        run_ends
+    }
+
+    // merges the sorted runs `arr[lo..mid]` and `arr[mid..hi]`, see `merge`
+    fn merge(&mut self, arr: &mut [T], lo: usize, mid: usize, hi: usize) {
+        // the runs are already in order, nothing to do
+        if mid == lo || mid == hi || !self.is_less(arr, mid, mid - 1) {
+            return;
+        }
+
+        self.scratch.clear();
+
+        if mid - lo <= hi - mid {
+            self.merge_lo(arr, lo, mid, hi);
+        } else {
+            self.merge_hi(arr, lo, mid, hi);
+        }
+    }
+
+    fn merge_lo(&mut self, arr: &mut [T], lo: usize, mid: usize, hi: usize) {
+        self.scratch.extend_from_slice(&arr[lo..mid]);
+
+        let (mut i, mut j, mut k) = (0, mid, lo);
+
+        while i < self.scratch.len() && j < hi {
+            self.tracer.trace(TraceEvent::Compare(j, lo + i));
+            let value = if (self.compare)(&arr[j], &self.scratch[i]) == Ordering::Less {
+                j += 1;
+                arr[j - 1].clone()
+            } else {
+                i += 1;
+                self.scratch[i - 1].clone()
+            };
+            self.write(arr, k, value);
+            k += 1;
+        }
+
+        // whatever is left of the right run is already in place
+        while i < self.scratch.len() {
+            let value = self.scratch[i].clone();
+            self.write(arr, k, value);
+            i += 1;
+            k += 1;
+        }
+    }
+
+    fn merge_hi(&mut self, arr: &mut [T], lo: usize, mid: usize, hi: usize) {
+        self.scratch.extend_from_slice(&arr[mid..hi]);
+
+        // `i` and `j` are where what is left of each run ends, `k` where the next largest goes
+        let (mut i, mut j, mut k) = (mid, self.scratch.len(), hi);
+
+        while i > lo && j > 0 {
+            k -= 1;
+            self.tracer.trace(TraceEvent::Compare(mid + j - 1, i - 1));
+            let value = if (self.compare)(&self.scratch[j - 1], &arr[i - 1]) == Ordering::Less {
+                i -= 1;
+                arr[i].clone()
+            } else {
+                j -= 1;
+                self.scratch[j].clone()
+            };
+            self.write(arr, k, value);
+        }
+
+        // whatever is left of the left run is already in place
+        while j > 0 {
+            j -= 1;
+            k -= 1;
+            let value = self.scratch[j].clone();
+            self.write(arr, k, value);
+        }
+    }
+
+    fn is_less(&mut self, arr: &[T], a: usize, b: usize) -> bool {
+        self.tracer.trace(TraceEvent::Compare(a, b));
+        (self.compare)(&arr[a], &arr[b]) == Ordering::Less
+    }
+
+    fn write(&mut self, arr: &mut [T], idx: usize, value: T) {
+        arr[idx] = value;
+        self.tracer.write(idx, &arr[idx]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::sort_check::is_sorted;
+
+    const VARIANTS: [MergeVariant; 3] = [
+        MergeVariant::TopDown,
+        MergeVariant::BottomUp,
+        MergeVariant::Natural,
+    ];
+
+    fn count_compares(arr: &mut [i32], variant: MergeVariant) -> usize {
+        let mut compares = 0;
+        merge_sort_by(arr, variant, |a, b| {
+            compares += 1;
+            a.cmp(b)
+        });
+        compares
+    }
+
+    #[test]
+    fn test_matches_std() {
+        let mut rng = Rng::new(42);
+
+        for len in 0..130 {
+            let input = rng.vec_i32(len, -20..20);
+            let mut expected = input.clone();
+            expected.sort();
+
+            for variant in VARIANTS {
+                let mut arr = input.clone();
+                merge_sort(&mut arr, variant);
+                assert_eq!(arr, expected, "{variant:?} on {input:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_stable_on_records() {
+        let mut rng = Rng::new(420);
+
+        for len in [0, 1, 2, 7, 64, 100, 1000] {
+            // (key, original position), few distinct keys so ties are everywhere
+            let input: Vec<(u8, usize)> = (0..len).map(|i| (rng.below(4) as u8, i)).collect();
+            let mut expected = input.clone();
+            expected.sort_by_key(|record| record.0);
+
+            for variant in VARIANTS {
+                let mut arr = input.clone();
+                merge_sort_by(&mut arr, variant, |a, b| a.0.cmp(&b.0));
+                assert_eq!(arr, expected, "{variant:?} on {len}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_natural_descending_runs_stay_stable() {
+        // strictly descending keys get reversed, the equal ones must not be
+        let mut arr = [(3, 'a'), (2, 'b'), (1, 'c'), (1, 'd'), (0, 'e'), (2, 'f')];
+        merge_sort_by(&mut arr, MergeVariant::Natural, |a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            arr,
+            [(0, 'e'), (1, 'c'), (1, 'd'), (2, 'b'), (2, 'f'), (3, 'a')]
+        );
+    }
+
+    #[test]
+    fn test_natural_is_linear_on_presorted_input() {
+        let len = 1000;
+
+        let mut sorted: Vec<i32> = (0..len).collect();
+        assert_eq!(count_compares(&mut sorted, MergeVariant::Natural), 999);
+
+        let mut reverse: Vec<i32> = (0..len).rev().collect();
+        assert_eq!(count_compares(&mut reverse, MergeVariant::Natural), 999);
+        assert!(reverse.windows(2).all(|w| w[0] < w[1]));
+
+        // two interleaved sorted halves need a single merge
+        let mut halves: Vec<i32> = (0..len).step_by(2).chain((1..len).step_by(2)).collect();
+        assert!(count_compares(&mut halves, MergeVariant::Natural) < 3 * len as usize);
+        assert_eq!(halves, (0..len).collect::<Vec<_>>());
+
+        let mut random = Rng::new(4).vec_i32(len as usize, 0..1000);
+        assert!(count_compares(&mut random, MergeVariant::Natural) > 5 * len as usize);
+    }
+
+    #[test]
+    fn test_reuses_the_scratch_buffer() {
+        let mut rng = Rng::new(421);
+        let mut scratch = Vec::with_capacity(500);
+
+        for variant in VARIANTS {
+            for _ in 0..10 {
+                let mut arr = rng.vec_i32(1000, 0..100);
+                merge_sort_with_buffer(&mut arr, variant, &mut scratch, i32::cmp);
+
+                assert!(is_sorted(&arr));
+                assert!(scratch.capacity() == 500, "the buffer never had to grow");
+            }
+        }
+    }
+}
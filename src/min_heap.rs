@@ -1,3 +1,7 @@
+use std::cmp::Ordering;
+
+use crate::tracer::{NoopTracer, TraceEvent, Tracer};
+
 pub struct MinHeap {
     pub data: Vec<f64>,
 }
@@ -48,32 +52,22 @@ impl MinHeap {
         Some(min_value)
     }
 
-    fn heapify_down(&mut self, mut idx: usize) {
-        let len = self.data.len();
-
-        loop {
-            let left_child = self.left_child(idx);
-            let right_child = self.right_child(idx);
-            let mut smallest = idx;
-
-            // find the smallest among parent and its children
-            if left_child < len && self.data[left_child] < self.data[smallest] {
-                smallest = left_child;
-            }
-
-            if right_child < len && self.data[right_child] < self.data[smallest] {
-                smallest = right_child;
-            }
+    /// consumes the heap and returns its values in ascending order
+    ///
+    /// sorts in place: the minimum is swapped behind the shrinking heap each
+    /// round, which leaves the values descending, then they are reversed
+    pub fn into_sorted_vec(mut self) -> Vec<f64> {
+        for end in (1..self.data.len()).rev() {
+            self.data.swap(0, end);
+            sift_down(&mut self.data[..end], 0, &mut f64_order, &mut NoopTracer);
+        }
 
-            // if current element is already the smallest, we're done
-            if smallest == idx {
-                break;
-            }
+        self.data.reverse();
+        self.data
+    }
 
-            // swap with the smallest child and continue
-            self.data.swap(idx, smallest);
-            idx = smallest;
-        }
+    fn heapify_down(&mut self, idx: usize) {
+        sift_down(&mut self.data, idx, &mut f64_order, &mut NoopTracer);
     }
 
     fn heapify_up(&mut self, mut idx: usize) {
@@ -120,6 +114,52 @@ impl MinHeap {
     }
 }
 
+// orders like `<` does, so a NaN never moves
+fn f64_order(a: &f64, b: &f64) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+/// the sift-down behind `MinHeap`, over any slice and ordering: moves
+/// `arr[idx]` down until neither child comes before it
+///
+/// with the comparison reversed it sifts for a max heap instead
+pub(crate) fn sift_down<T, F>(
+    arr: &mut [T],
+    mut idx: usize,
+    compare: &mut F,
+    tracer: &mut impl Tracer,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+
+    loop {
+        let left_child = 2 * idx + 1;
+        let right_child = 2 * idx + 2;
+        let mut smallest = idx;
+
+        // find the smallest among parent and its children
+        for child in [left_child, right_child] {
+            if child < len {
+                tracer.trace(TraceEvent::Compare(child, smallest));
+                if compare(&arr[child], &arr[smallest]) == Ordering::Less {
+                    smallest = child;
+                }
+            }
+        }
+
+        // if current element is already the smallest, we're done
+        if smallest == idx {
+            break;
+        }
+
+        // swap with the smallest child and continue
+        tracer.trace(TraceEvent::Swap(idx, smallest));
+        arr.swap(idx, smallest);
+        idx = smallest;
+    }
+}
+
 impl Default for MinHeap {
     fn default() -> Self {
         Self::new()
@@ -127,7 +167,7 @@ impl Default for MinHeap {
 }
 
 impl From<Vec<f64>> for MinHeap {
-    fn from(mut values: Vec<f64>) -> Self {
+    fn from(values: Vec<f64>) -> Self {
         // build heap from bottom up (more efficient than inserting one by one)
         if values.is_empty() {
             return MinHeap::new();
@@ -149,6 +189,7 @@ impl From<Vec<f64>> for MinHeap {
 #[cfg(test)]
 mod tests {
     use crate::min_heap::MinHeap;
+    use crate::rng::Rng;
 
     #[test]
     fn test_new_heap() {
@@ -287,4 +328,23 @@ mod tests {
 
         assert!(heap.is_empty());
     }
+
+    #[test]
+    fn test_into_sorted_vec() {
+        let mut rng = Rng::new(44);
+
+        for len in 0..100 {
+            let values: Vec<f64> = (0..len).map(|_| rng.range_i32(-50..50) as f64).collect();
+            let mut expected = values.clone();
+            expected.sort_by(f64::total_cmp);
+
+            assert_eq!(MinHeap::from(values).into_sorted_vec(), expected);
+        }
+
+        let mut heap = MinHeap::new();
+        for value in [2.5, -1.0, 9.0, 0.0] {
+            heap.insert(value);
+        }
+        assert_eq!(heap.into_sorted_vec(), vec![-1.0, 0.0, 2.5, 9.0]);
+    }
 }
@@ -0,0 +1,271 @@
+use std::cmp::Ordering;
+use std::thread;
+
+use crate::merge_sort::{MergeVariant, merge, merge_sort_by};
+use crate::quicksort::{intro_sort_by, partition};
+use crate::tracer::NoopTracer;
+
+/// how much parallelism the parallel sorts may use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParallelConfig {
+    /// most threads sorting at once, the calling thread included
+    pub threads: usize,
+    /// ranges this short or shorter are sorted on the current thread
+    pub cutoff: usize,
+}
+
+impl ParallelConfig {
+    pub fn new(threads: usize) -> Self {
+        ParallelConfig {
+            threads: threads.max(1),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_cutoff(self, cutoff: usize) -> Self {
+        ParallelConfig { cutoff, ..self }
+    }
+}
+
+impl Default for ParallelConfig {
+    /// one thread per core, and ranges too short to be worth a thread
+    fn default() -> Self {
+        ParallelConfig {
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            cutoff: 8192,
+        }
+    }
+}
+
+pub fn par_quick_sort<T: Ord + Send>(arr: &mut [T], config: ParallelConfig) {
+    par_quick_sort_by(arr, config, T::cmp);
+}
+
+/// quicksort that hands one side of every partition to a new thread until the
+/// thread budget is spent, then finishes each range with introsort
+///
+/// not stable, but the result only depends on the input and the config,
+/// never on how the threads happen to be scheduled
+pub fn par_quick_sort_by<T, F>(arr: &mut [T], config: ParallelConfig, compare: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    quick_sort_rec(arr, config.threads.max(1), config.cutoff, &compare);
+}
+
+fn quick_sort_rec<T, F>(arr: &mut [T], threads: usize, cutoff: usize, compare: &F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if threads <= 1 || arr.len() <= cutoff.max(2) {
+        intro_sort_by(arr, compare);
+        return;
+    }
+
+    let hi = arr.len() - 1;
+    let mid = hi / 2;
+    let pivot = median_of_three(arr, 0, mid, hi, compare);
+    arr.swap(pivot, hi);
+
+    let pivot = partition(arr, 0, hi, &mut &*compare, &mut NoopTracer);
+    let (left, right) = arr.split_at_mut(pivot);
+    let right = &mut right[1..];
+
+    // both sides run at the same time, so the budget is split between them
+    let left_threads = threads / 2;
+    let right_threads = threads - left_threads;
+
+    thread::scope(|scope| {
+        scope.spawn(|| quick_sort_rec(left, left_threads, cutoff, compare));
+        quick_sort_rec(right, right_threads, cutoff, compare);
+    });
+}
+
+fn median_of_three<T, F>(arr: &[T], a: usize, b: usize, c: usize, compare: &F) -> usize
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let less = |x: usize, y: usize| compare(&arr[x], &arr[y]) == Ordering::Less;
+    let (a, b) = if less(b, a) { (b, a) } else { (a, b) };
+
+    if less(c, a) {
+        a
+    } else if less(b, c) {
+        b
+    } else {
+        c
+    }
+}
+
+pub fn par_merge_sort<T: Ord + Clone + Send>(arr: &mut [T], config: ParallelConfig) {
+    par_merge_sort_by(arr, config, T::cmp);
+}
+
+/// top down merge sort that sorts the two halves on separate threads until the
+/// thread budget is spent, then finishes each half with `merge_sort_by`
+///
+/// stable, so the result is the same as `slice::sort_by` for any config
+pub fn par_merge_sort_by<T, F>(arr: &mut [T], config: ParallelConfig, compare: F)
+where
+    T: Clone + Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    merge_sort_rec(arr, config.threads.max(1), config.cutoff, &compare);
+}
+
+fn merge_sort_rec<T, F>(arr: &mut [T], threads: usize, cutoff: usize, compare: &F)
+where
+    T: Clone + Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if threads <= 1 || arr.len() <= cutoff.max(2) {
+        merge_sort_by(arr, MergeVariant::TopDown, compare);
+        return;
+    }
+
+    let mid = arr.len() / 2;
+    let (left, right) = arr.split_at_mut(mid);
+
+    let left_threads = threads / 2;
+    let right_threads = threads - left_threads;
+
+    thread::scope(|scope| {
+        scope.spawn(|| merge_sort_rec(left, left_threads, cutoff, compare));
+        merge_sort_rec(right, right_threads, cutoff, compare);
+    });
+
+    let mut compare = compare;
+    merge(arr, mid, &mut Vec::with_capacity(mid), &mut compare);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::sort_check::is_sorted;
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    const THREADS: [usize; 5] = [1, 2, 3, 4, 8];
+
+    fn random_pairs(rng: &mut Rng, len: usize) -> Vec<(u8, u32)> {
+        (0..len as u32).map(|i| (rng.below(16) as u8, i)).collect()
+    }
+
+    #[test]
+    fn test_par_quick_sort_matches_std() {
+        let mut rng = Rng::new(41);
+
+        for len in [0, 1, 2, 10, 100, 1000, 20_000] {
+            let input = rng.vec_i32(len, -1000..1000);
+            let mut expected = input.clone();
+            expected.sort();
+
+            for threads in THREADS {
+                let config = ParallelConfig::new(threads).with_cutoff(64);
+                let mut arr = input.clone();
+                par_quick_sort(&mut arr, config);
+                assert_eq!(arr, expected, "{threads} threads on {len}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_par_merge_sort_is_stable() {
+        let mut rng = Rng::new(410);
+
+        for len in [0, 1, 2, 10, 100, 1000, 20_000] {
+            let input = random_pairs(&mut rng, len);
+            let mut expected = input.clone();
+            expected.sort_by_key(|pair| pair.0);
+
+            for threads in THREADS {
+                let config = ParallelConfig::new(threads).with_cutoff(64);
+                let mut arr = input.clone();
+                par_merge_sort_by(&mut arr, config, |a, b| a.0.cmp(&b.0));
+                assert_eq!(arr, expected, "{threads} threads on {len}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_par_quick_sort_is_deterministic() {
+        let mut rng = Rng::new(411);
+        let input = random_pairs(&mut rng, 50_000);
+        let config = ParallelConfig::new(4).with_cutoff(256);
+
+        // equal keys are free to end up in any order, but always the same one
+        let run = || {
+            let mut arr = input.clone();
+            par_quick_sort_by(&mut arr, config, |a, b| a.0.cmp(&b.0));
+            arr
+        };
+
+        let first = run();
+        assert!(first.windows(2).all(|w| w[0].0 <= w[1].0));
+
+        for _ in 0..5 {
+            assert_eq!(run(), first);
+        }
+    }
+
+    #[test]
+    fn test_thread_budget_is_respected() {
+        let mut rng = Rng::new(412);
+        let input = rng.vec_i32(10_000, 0..i32::MAX);
+
+        for threads in [1, 3, 4] {
+            let config = ParallelConfig::new(threads).with_cutoff(500);
+
+            let seen = Mutex::new(HashSet::new());
+            let compare = |a: &i32, b: &i32| {
+                seen.lock().unwrap().insert(thread::current().id());
+                a.cmp(b)
+            };
+
+            let mut arr = input.clone();
+            par_quick_sort_by(&mut arr, config, compare);
+            assert_eq!(seen.lock().unwrap().len(), threads, "quick");
+
+            seen.lock().unwrap().clear();
+            let mut arr = input.clone();
+            par_merge_sort_by(&mut arr, config, compare);
+            assert_eq!(seen.lock().unwrap().len(), threads, "merge");
+        }
+    }
+
+    /// run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_against_sequential() {
+        use crate::quicksort::quick_sort_by;
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        let mut rng = Rng::new(1);
+        let input: Vec<i64> = (0..4_000_000)
+            .map(|_| rng.range_i64(i64::MIN..i64::MAX))
+            .collect();
+        let config = ParallelConfig::default();
+        println!("{} threads", config.threads);
+
+        let time = |name: &str, sort: &dyn Fn(&mut [i64])| {
+            let mut arr = input.clone();
+            let start = Instant::now();
+            sort(black_box(&mut arr));
+            println!("{name:<20} {:?}", start.elapsed());
+            assert!(is_sorted(&arr));
+        };
+
+        time("quick_sort", &|arr| quick_sort_by(arr, i64::cmp));
+        time("intro_sort", &|arr| intro_sort_by(arr, i64::cmp));
+        time("par_quick_sort", &|arr| par_quick_sort(arr, config));
+        time("par_merge_sort (1)", &|arr| {
+            par_merge_sort(arr, ParallelConfig::new(1))
+        });
+        time("par_merge_sort", &|arr| par_merge_sort(arr, config));
+        time("slice::sort", &|arr| arr.sort());
+    }
+}
@@ -69,7 +69,7 @@ impl<T> Queue<T> {
         }
     }
 
-    pub fn peek(&self) -> Option<Ref<T>> {
+    pub fn peek(&self) -> Option<Ref<'_, T>> {
         self.head
             .as_ref()
             .map(|head| Ref::map(head.borrow(), |node| &node.value))
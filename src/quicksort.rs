@@ -1,33 +1,152 @@
+use std::cmp::Ordering;
+
+use crate::heap_sort::heap_sort_by_traced;
+use crate::rng::Rng;
+use crate::tracer::{NoopTracer, TraceEvent, Tracer};
+
+/// how quicksort picks the pivot of each range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PivotStrategy {
+    First,
+    /// what plain `quick_sort` uses
+    Last,
+    /// median of the first, middle and last elements
+    MedianOfThree,
+    /// Tukey's ninther, the median of three medians of three spread over the range
+    Ninther,
+    /// uniformly random element, drawn from a generator seeded with the given value
+    Random(u64),
+}
+
+/// how a range is split around its pivot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionScheme {
+    /// Lomuto's two way split, elements equal to the pivot end up on its left
+    Lomuto,
+    /// Dutch national flag split into less, equal and greater,
+    /// so runs of equal keys are never recursed into
+    ThreeWay,
+}
+
+// below this length the ninther falls back to a plain median of three
+const NINTHER_THRESHOLD: usize = 40;
+
+// introsort leaves ranges up to this length to insertion sort
+const INSERTION_THRESHOLD: usize = 16;
+
 // this function to check when point low and high is meet
-fn qs(arr: &mut [i32], lo: usize, hi: usize) {
-    if lo >= hi {
-        return;
-    }
+//
+// recurses into the smaller side and loops on the larger one, so the stack
+// stays O(log n) even when the pivots are bad, `depth` counts the partitioning
+// rounds above this range
+fn qs<T, F>(
+    arr: &mut [T],
+    mut lo: usize,
+    mut hi: usize,
+    mut depth: usize,
+    strategy: &mut Strategy,
+    compare: &mut F,
+    tracer: &mut impl Tracer,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    while lo < hi {
+        if hi - lo < strategy.insertion_len {
+            insertion_sort(arr, lo, hi, compare, tracer);
+            return;
+        }
 
-    let pivot_idx = partition(arr, lo, hi);
+        // the pivots keep going bad, so give up on quicksort for this range
+        if depth == strategy.max_depth {
+            heap_sort(arr, lo, hi, compare, tracer);
+            return;
+        }
+        depth += 1;
+
+        // everything before `left_end` and from `right_start` on is still unsorted
+        let (left_end, right_start) = split(arr, lo, hi, strategy, compare, tracer);
 
-    // we recurse to two sides of array but not included with pivot index
-    // Only recurse left if there are elements to the left
-    if pivot_idx > lo {
-        qs(arr, lo, pivot_idx - 1);
+        // we recurse to two sides of array but not included with the pivots
+        if left_end - lo < hi + 1 - right_start {
+            // Only recurse left if there are elements to the left
+            if left_end > lo {
+                qs(arr, lo, left_end - 1, depth, strategy, compare, tracer);
+            }
+            lo = right_start;
+        } else {
+            // Only recurse right if there are elements to the right
+            if right_start < hi {
+                qs(arr, right_start, hi, depth, strategy, compare, tracer);
+            }
+            if left_end == lo {
+                return;
+            }
+            hi = left_end - 1;
+        }
     }
+}
+
+/// how `qs` picks its pivots and splits its ranges for one sort
+struct Strategy {
+    pivot: PivotStrategy,
+    scheme: PartitionScheme,
+    rng: Rng,
+    // partitioning rounds allowed before a range is handed to heapsort
+    max_depth: usize,
+    // ranges this short or shorter are finished with insertion sort
+    insertion_len: usize,
+}
+
+// picks a pivot for `lo..=hi` and partitions around it, returns where the
+// block of pivots starts and where the part after it starts
+fn split<T, F>(
+    arr: &mut [T],
+    lo: usize,
+    hi: usize,
+    strategy: &mut Strategy,
+    compare: &mut F,
+    tracer: &mut impl Tracer,
+) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let pivot = choose_pivot(arr, lo, hi, strategy, compare, tracer);
 
-    // Only recurse right if there are elements to the right
-    if pivot_idx < hi {
-        qs(arr, pivot_idx + 1, hi);
+    match strategy.scheme {
+        PartitionScheme::Lomuto => {
+            swap(arr, pivot, hi, tracer);
+            let idx = partition(arr, lo, hi, compare, tracer);
+            (idx, idx + 1)
+        }
+        PartitionScheme::ThreeWay => {
+            swap(arr, pivot, lo, tracer);
+            partition_three_way(arr, lo, hi, compare, tracer)
+        }
     }
 }
 
 // this will return a number which will be repesentation of pivot index
 // where did we end up splitting the array
-fn partition(arr: &mut [i32], lo: usize, hi: usize) -> usize {
-    let pivot = arr[hi];
+//
+// the pivot stays at `hi` until the final swap, so it is compared in place
+// instead of being copied out
+pub(crate) fn partition<T, F>(
+    arr: &mut [T],
+    lo: usize,
+    hi: usize,
+    compare: &mut F,
+    tracer: &mut impl Tracer,
+) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     let mut idx = lo;
 
     for i in lo..hi {
-        println!("{i:#}");
-        if arr[i] <= pivot {
+        tracer.trace(TraceEvent::Compare(i, hi));
+        if compare(&arr[i], &arr[hi]) != Ordering::Greater {
             if idx != i {
+                tracer.trace(TraceEvent::Swap(i, idx));
                 // manual way of swapping array
                 // let tmp = arr[i];
                 // arr[i] = arr[idx];
@@ -41,22 +160,348 @@ fn partition(arr: &mut [i32], lo: usize, hi: usize) -> usize {
     // manual way of swapping array
     // arr[hi] = arr[idx];
     // arr[idx] = pivot;
+    if idx != hi {
+        tracer.trace(TraceEvent::Swap(hi, idx));
+    }
     arr.swap(hi, idx);
 
     idx
 }
 
+// splits `lo..=hi` into `< pivot`, `== pivot` and `> pivot` with the pivot
+// starting at `lo`, returns where the equal block starts and ends
+//
+// the equal block is never empty, so `arr[lt]` always holds a copy of the pivot
+fn partition_three_way<T, F>(
+    arr: &mut [T],
+    lo: usize,
+    hi: usize,
+    compare: &mut F,
+    tracer: &mut impl Tracer,
+) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut lt = lo;
+    let mut i = lo + 1;
+    let mut gt = hi + 1;
+
+    while i < gt {
+        tracer.trace(TraceEvent::Compare(i, lt));
+        match compare(&arr[i], &arr[lt]) {
+            Ordering::Less => {
+                swap(arr, lt, i, tracer);
+                lt += 1;
+                i += 1;
+            }
+            Ordering::Greater => {
+                gt -= 1;
+                swap(arr, i, gt, tracer);
+            }
+            Ordering::Equal => i += 1,
+        }
+    }
+
+    (lt, gt)
+}
+
+fn choose_pivot<T, F>(
+    arr: &[T],
+    lo: usize,
+    hi: usize,
+    strategy: &mut Strategy,
+    compare: &mut F,
+    tracer: &mut impl Tracer,
+) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mid = lo + (hi - lo) / 2;
+    let mut median = |a, b, c| median_of_three(arr, a, b, c, compare, tracer);
+
+    match strategy.pivot {
+        PivotStrategy::First => lo,
+        PivotStrategy::Last => hi,
+        PivotStrategy::MedianOfThree => median(lo, mid, hi),
+        PivotStrategy::Ninther if hi - lo + 1 < NINTHER_THRESHOLD => median(lo, mid, hi),
+        PivotStrategy::Ninther => {
+            let step = (hi - lo + 1) / 8;
+            let a = median(lo, lo + step, lo + 2 * step);
+            let b = median(mid - step, mid, mid + step);
+            let c = median(hi - 2 * step, hi - step, hi);
+            median(a, b, c)
+        }
+        PivotStrategy::Random(_) => strategy.rng.range_usize(lo..hi + 1),
+    }
+}
+
+fn median_of_three<T, F>(
+    arr: &[T],
+    a: usize,
+    b: usize,
+    c: usize,
+    compare: &mut F,
+    tracer: &mut impl Tracer,
+) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let (a, b) = if is_less(arr, b, a, compare, tracer) {
+        (b, a)
+    } else {
+        (a, b)
+    };
+
+    // arr[a] <= arr[b] from here on
+    if is_less(arr, c, a, compare, tracer) {
+        a
+    } else if is_less(arr, b, c, compare, tracer) {
+        b
+    } else {
+        c
+    }
+}
+
+fn insertion_sort<T, F>(
+    arr: &mut [T],
+    lo: usize,
+    hi: usize,
+    compare: &mut F,
+    tracer: &mut impl Tracer,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in lo + 1..=hi {
+        let mut j = i;
+        while j > lo && is_less(arr, j, j - 1, compare, tracer) {
+            swap(arr, j - 1, j, tracer);
+            j -= 1;
+        }
+    }
+}
+
+fn heap_sort<T, F>(arr: &mut [T], lo: usize, hi: usize, compare: &mut F, tracer: &mut impl Tracer)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // the heap only sees the subslice, so move its indices back into place
+    heap_sort_by_traced(&mut arr[lo..=hi], compare, &mut |event: TraceEvent| {
+        tracer.trace(event.shifted(lo))
+    });
+}
+
+fn is_less<T, F>(arr: &[T], a: usize, b: usize, compare: &mut F, tracer: &mut impl Tracer) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    tracer.trace(TraceEvent::Compare(a, b));
+    compare(&arr[a], &arr[b]) == Ordering::Less
+}
+
+fn swap<T>(arr: &mut [T], a: usize, b: usize, tracer: &mut impl Tracer) {
+    if a != b {
+        tracer.trace(TraceEvent::Swap(a, b));
+        arr.swap(a, b);
+    }
+}
+
 fn quick_sort(arr: &mut [i32]) {
+    quick_sort_traced(arr, &mut NoopTracer);
+}
+
+/// same as `quick_sort`, reporting every comparison and swap to the tracer
+fn quick_sort_traced(arr: &mut [i32], tracer: &mut impl Tracer) {
+    quick_sort_by_traced(arr, i32::cmp, tracer);
+}
+
+/// quicksort over any element type, ordered by `compare`
+/// not stable, equal elements may be reordered
+pub fn quick_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quick_sort_by_traced(arr, compare, &mut NoopTracer);
+}
+
+pub fn quick_sort_by_traced<T, F>(arr: &mut [T], compare: F, tracer: &mut impl Tracer)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quick_sort_with_traced(
+        arr,
+        PivotStrategy::Last,
+        PartitionScheme::Lomuto,
+        compare,
+        tracer,
+    );
+}
+
+/// quicksort with a chosen pivot strategy and partition scheme
+pub fn quick_sort_with<T, F>(
+    arr: &mut [T],
+    pivot: PivotStrategy,
+    scheme: PartitionScheme,
+    compare: F,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quick_sort_with_traced(arr, pivot, scheme, compare, &mut NoopTracer);
+}
+
+pub fn quick_sort_with_traced<T, F>(
+    arr: &mut [T],
+    pivot: PivotStrategy,
+    scheme: PartitionScheme,
+    mut compare: F,
+    tracer: &mut impl Tracer,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
     if arr.len() <= 1 {
         // Handle empty and single-element arrays
         return;
     }
-    qs(arr, 0, arr.len() - 1);
+
+    let seed = match pivot {
+        PivotStrategy::Random(seed) => seed,
+        _ => 0,
+    };
+
+    let mut strategy = Strategy {
+        pivot,
+        scheme,
+        rng: Rng::new(seed),
+        max_depth: usize::MAX,
+        insertion_len: 0,
+    };
+    qs(
+        arr,
+        0,
+        arr.len() - 1,
+        0,
+        &mut strategy,
+        &mut compare,
+        tracer,
+    );
+}
+
+/// introsort: quicksort that falls back to heapsort once it has partitioned
+/// 2·log2(n) levels deep, so it stays O(n log n) whatever the input, and that
+/// leaves short ranges to insertion sort
+///
+/// uses ninther pivots with three way partitioning, not stable
+pub fn intro_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    intro_sort_by_traced(arr, compare, &mut NoopTracer);
+}
+
+pub fn intro_sort_by_traced<T, F>(arr: &mut [T], mut compare: F, tracer: &mut impl Tracer)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if arr.len() <= 1 {
+        return;
+    }
+
+    let mut strategy = Strategy {
+        pivot: PivotStrategy::Ninther,
+        scheme: PartitionScheme::ThreeWay,
+        rng: Rng::new(0),
+        max_depth: 2 * arr.len().ilog2() as usize,
+        insertion_len: INSERTION_THRESHOLD,
+    };
+    qs(
+        arr,
+        0,
+        arr.len() - 1,
+        0,
+        &mut strategy,
+        &mut compare,
+        tracer,
+    );
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rng::Rng;
+    use crate::sort_check::is_sorted;
+    use crate::tracer::{CountingTracer, RecordingTracer};
+
+    const STRATEGIES: [PivotStrategy; 5] = [
+        PivotStrategy::First,
+        PivotStrategy::Last,
+        PivotStrategy::MedianOfThree,
+        PivotStrategy::Ninther,
+        PivotStrategy::Random(39),
+    ];
+
+    const SCHEMES: [PartitionScheme; 2] = [PartitionScheme::Lomuto, PartitionScheme::ThreeWay];
+
+    // the input shapes the pivot strategies are judged on
+    fn shaped_inputs(rng: &mut Rng, len: usize) -> [(&'static str, Vec<i32>); 5] {
+        [
+            ("sorted", (0..len as i32).collect()),
+            ("reverse", (0..len as i32).rev().collect()),
+            ("all equal", vec![7; len]),
+            ("few distinct", rng.vec_i32(len, 0..4)),
+            ("random", rng.vec_i32(len, i32::MIN..i32::MAX)),
+        ]
+    }
+
+    /// McIlroy's "killer adversary" for quicksort: elements start out as gas,
+    /// worth more than anything solid, and are only frozen to a concrete value
+    /// when two gas elements are compared, always freezing the one that doesn't
+    /// look like the pivot. any deterministic quicksort ends up quadratic
+    ///
+    /// sorts the ids `0..len` with `sort` and returns how many comparisons it took,
+    /// along with the values the adversary settled on, a killer input for `sort`
+    fn run_adversary(len: usize, sort: impl FnOnce(&mut [usize], &mut Cmp)) -> (usize, Vec<i32>) {
+        let gas = len;
+        let mut values = vec![gas; len];
+        let mut solid = 0;
+        let mut candidate = 0;
+        let mut compares = 0;
+
+        let mut compare = |x: &usize, y: &usize| {
+            let (x, y) = (*x, *y);
+            compares += 1;
+
+            if values[x] == gas && values[y] == gas {
+                let frozen = if x == candidate { x } else { y };
+                values[frozen] = solid;
+                solid += 1;
+            }
+
+            if values[x] == gas {
+                candidate = x;
+            } else if values[y] == gas {
+                candidate = y;
+            }
+
+            values[x].cmp(&values[y])
+        };
+
+        let mut ids: Vec<usize> = (0..len).collect();
+        sort(&mut ids, &mut compare);
+
+        assert!(ids.windows(2).all(|w| values[w[0]] <= values[w[1]]));
+        (compares, values.iter().map(|&v| v as i32).collect())
+    }
+
+    type Cmp<'a> = dyn FnMut(&usize, &usize) -> Ordering + 'a;
+
+    fn compares_with(arr: &[i32], pivot: PivotStrategy, scheme: PartitionScheme) -> usize {
+        let mut arr = arr.to_vec();
+        let mut counter = CountingTracer::default();
+
+        quick_sort_with_traced(&mut arr, pivot, scheme, i32::cmp, &mut counter);
+        assert!(is_sorted(&arr));
+
+        counter.compares
+    }
 
     #[test]
     fn test_basic_unsorted_array() {
@@ -99,4 +544,265 @@ mod tests {
         quick_sort(&mut arr);
         assert_eq!(arr, []);
     }
+
+    #[test]
+    fn test_traced_swaps_replay_to_sorted() {
+        let original = [5, 1, 4, 2, 8, 3, 7, 6];
+        let mut arr = original;
+        let mut recorder = RecordingTracer::default();
+
+        quick_sort_traced(&mut arr, &mut recorder);
+        assert_eq!(arr, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+        // applying the recorded swaps to the input reproduces the sort
+        let mut replay = original;
+        for event in &recorder.events {
+            if let TraceEvent::Swap(a, b) = *event {
+                replay.swap(a, b);
+            }
+        }
+        assert_eq!(replay, arr);
+    }
+
+    #[test]
+    fn test_traced_counts_quadratic_compares_on_sorted_input() {
+        let mut arr: Vec<i32> = (0..20).collect();
+        let mut counter = CountingTracer::default();
+
+        quick_sort_traced(&mut arr, &mut counter);
+
+        // last element pivot on sorted input compares every pair once
+        assert_eq!(counter.compares, 20 * 19 / 2);
+        assert_eq!(counter.swaps, 0);
+    }
+
+    #[test]
+    fn test_every_strategy_and_scheme_sorts() {
+        let mut rng = Rng::new(39);
+
+        for len in [0, 1, 2, 3, 10, 39, 40, 41, 100, 257] {
+            for (shape, input) in shaped_inputs(&mut rng, len) {
+                let mut expected = input.clone();
+                expected.sort();
+
+                for pivot in STRATEGIES {
+                    for scheme in SCHEMES {
+                        let mut arr = input.clone();
+                        quick_sort_with(&mut arr, pivot, scheme, i32::cmp);
+                        assert_eq!(arr, expected, "{pivot:?} {scheme:?} on {len} {shape}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generic_elements_by_key() {
+        let mut rng = Rng::new(390);
+        let input: Vec<(u8, String)> = (0..300)
+            .map(|i| (rng.below(5) as u8, format!("item-{i}")))
+            .collect();
+
+        for pivot in STRATEGIES {
+            for scheme in SCHEMES {
+                let mut arr = input.clone();
+                quick_sort_with(&mut arr, pivot, scheme, |a, b| a.0.cmp(&b.0));
+                assert!(arr.windows(2).all(|w| w[0].0 <= w[1].0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_median_pivots_avoid_quadratic_on_sorted_input() {
+        let len = 2000;
+        let sorted: Vec<i32> = (0..len).collect();
+        let reverse: Vec<i32> = (0..len).rev().collect();
+        let quadratic = (len * (len - 1) / 2) as usize;
+
+        for input in [&sorted, &reverse] {
+            assert_eq!(
+                compares_with(input, PivotStrategy::First, PartitionScheme::Lomuto),
+                quadratic
+            );
+
+            for pivot in [
+                PivotStrategy::MedianOfThree,
+                PivotStrategy::Ninther,
+                PivotStrategy::Random(1),
+            ] {
+                let compares = compares_with(input, pivot, PartitionScheme::Lomuto);
+                assert!(compares < quadratic / 20, "{pivot:?} took {compares}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_three_way_is_linear_on_all_equal_input() {
+        let equal = vec![5; 2000];
+
+        // a single pass puts everything in the equal block
+        assert_eq!(
+            compares_with(&equal, PivotStrategy::Last, PartitionScheme::ThreeWay),
+            1999
+        );
+        assert_eq!(
+            compares_with(&equal, PivotStrategy::Last, PartitionScheme::Lomuto),
+            2000 * 1999 / 2
+        );
+    }
+
+    #[test]
+    fn test_random_pivot_is_deterministic_per_seed() {
+        let mut rng = Rng::new(3);
+        let input = rng.vec_i32(500, 0..100);
+
+        let record = |seed| {
+            let mut arr = input.clone();
+            let mut recorder = RecordingTracer::default();
+            quick_sort_with_traced(
+                &mut arr,
+                PivotStrategy::Random(seed),
+                PartitionScheme::ThreeWay,
+                i32::cmp,
+                &mut recorder,
+            );
+            recorder.events
+        };
+
+        assert_eq!(record(7), record(7));
+        assert_ne!(record(7), record(8));
+    }
+
+    #[test]
+    fn test_three_way_swaps_replay_to_sorted() {
+        let mut rng = Rng::new(4);
+        let original = rng.vec_i32(200, 0..10);
+        let mut arr = original.clone();
+        let mut recorder = RecordingTracer::default();
+
+        quick_sort_with_traced(
+            &mut arr,
+            PivotStrategy::Ninther,
+            PartitionScheme::ThreeWay,
+            i32::cmp,
+            &mut recorder,
+        );
+
+        let mut replay = original;
+        for event in &recorder.events {
+            if let TraceEvent::Swap(a, b) = *event {
+                replay.swap(a, b);
+            }
+        }
+        assert_eq!(replay, arr);
+    }
+
+    /// run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_pivot_strategies() {
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        // kept small enough that the quadratic cases still finish
+        let len = 20_000;
+        let mut rng = Rng::new(1);
+
+        println!(
+            "{:<14} {:<14} {:<10} {:>12}",
+            "shape", "pivot", "scheme", "time"
+        );
+
+        for (shape, input) in shaped_inputs(&mut rng, len) {
+            for pivot in STRATEGIES {
+                for scheme in SCHEMES {
+                    let mut arr = input.clone();
+
+                    let start = Instant::now();
+                    quick_sort_with(black_box(&mut arr), pivot, scheme, i32::cmp);
+                    let elapsed = start.elapsed();
+
+                    let pivot = format!("{pivot:?}");
+                    let scheme = format!("{scheme:?}");
+                    println!("{shape:<14} {pivot:<14} {scheme:<10} {elapsed:>12?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_intro_sort_matches_std() {
+        let mut rng = Rng::new(40);
+
+        for len in [0, 1, 2, 15, 16, 17, 18, 33, 100, 1000, 5000] {
+            for (shape, input) in shaped_inputs(&mut rng, len) {
+                let mut arr = input.clone();
+                let mut expected = input;
+                expected.sort();
+
+                intro_sort_by(&mut arr, i32::cmp);
+                assert_eq!(arr, expected, "{len} {shape}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_adversary_breaks_plain_quicksort_but_not_intro_sort() {
+        let len: usize = 3000;
+        let n_log_n = len * len.ilog2() as usize;
+
+        let (plain, _) = run_adversary(len, |arr, compare| {
+            quick_sort_with(
+                arr,
+                PivotStrategy::MedianOfThree,
+                PartitionScheme::Lomuto,
+                compare,
+            )
+        });
+        assert!(plain > len * len / 8, "only {plain} compares");
+
+        let (intro, _) = run_adversary(len, |arr, compare| intro_sort_by(arr, compare));
+        assert!(intro < 6 * n_log_n, "{intro} compares");
+    }
+
+    #[test]
+    fn test_intro_sort_falls_back_to_heapsort_on_killer_input() {
+        // a fixed input that defeats introsort's own pivot choice
+        let len: usize = 2000;
+        let (_, killer) = run_adversary(len, |arr, compare| {
+            quick_sort_with(
+                arr,
+                PivotStrategy::Ninther,
+                PartitionScheme::ThreeWay,
+                compare,
+            )
+        });
+        let quadratic = len * len / 16;
+        let n_log_n = len * len.ilog2() as usize;
+
+        let mut counter = CountingTracer::default();
+        let mut arr = killer.clone();
+        quick_sort_with_traced(
+            &mut arr,
+            PivotStrategy::Ninther,
+            PartitionScheme::ThreeWay,
+            i32::cmp,
+            &mut counter,
+        );
+        assert!(
+            counter.compares > quadratic,
+            "{} compares",
+            counter.compares
+        );
+
+        let mut counter = CountingTracer::default();
+        let mut arr = killer;
+        intro_sort_by_traced(&mut arr, i32::cmp, &mut counter);
+        assert!(is_sorted(&arr));
+        assert!(
+            counter.compares < 6 * n_log_n,
+            "{} compares",
+            counter.compares
+        );
+    }
 }
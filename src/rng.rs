@@ -0,0 +1,138 @@
+use std::ops::Range;
+
+/// small seeded pseudo random number generator (splitmix64)
+/// good enough to drive tests and benchmarks, not meant for cryptography
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// uniform value in `0..bound`, rejecting the biased tail of the u64 range
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "bound must be positive");
+
+        let zone = u64::MAX - u64::MAX % bound;
+
+        loop {
+            let value = self.next_u64();
+
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    pub fn range_i64(&mut self, range: Range<i64>) -> i64 {
+        assert!(range.start < range.end, "range must not be empty");
+
+        let span = range.end.abs_diff(range.start);
+        range.start.wrapping_add(self.below(span) as i64)
+    }
+
+    pub fn range_i32(&mut self, range: Range<i32>) -> i32 {
+        self.range_i64(range.start as i64..range.end as i64) as i32
+    }
+
+    pub fn range_usize(&mut self, range: Range<usize>) -> usize {
+        assert!(range.start < range.end, "range must not be empty");
+
+        range.start + self.below((range.end - range.start) as u64) as usize
+    }
+
+    /// uniform value in `[0, 1)`
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    /// fisher-yates shuffle
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range_usize(0..i + 1);
+            items.swap(i, j);
+        }
+    }
+
+    /// vector of `len` values drawn uniformly from `range`
+    pub fn vec_i32(&mut self, len: usize, range: Range<i32>) -> Vec<i32> {
+        (0..len).map(|_| self.range_i32(range.clone())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_different_seed_different_sequence() {
+        let mut a = Rng::new(1);
+        let mut b = Rng::new(2);
+
+        let a_values: Vec<u64> = (0..10).map(|_| a.next_u64()).collect();
+        let b_values: Vec<u64> = (0..10).map(|_| b.next_u64()).collect();
+
+        assert_ne!(a_values, b_values);
+    }
+
+    #[test]
+    fn test_ranges_stay_in_bounds() {
+        let mut rng = Rng::new(7);
+
+        for _ in 0..1000 {
+            let v = rng.range_i32(-5..5);
+            assert!((-5..5).contains(&v));
+
+            let u = rng.range_usize(3..4);
+            assert_eq!(u, 3);
+
+            let f = rng.next_f64();
+            assert!((0.0..1.0).contains(&f));
+        }
+
+        let extreme = rng.range_i64(i64::MIN..i64::MAX);
+        assert!(extreme < i64::MAX);
+
+        let values = rng.vec_i32(50, 10..20);
+        assert_eq!(values.len(), 50);
+        assert!(values.iter().all(|v| (10..20).contains(v)));
+    }
+
+    #[test]
+    fn test_shuffle_is_a_permutation() {
+        let mut rng = Rng::new(3);
+        let mut values: Vec<i32> = (0..50).collect();
+
+        rng.shuffle(&mut values);
+        assert_ne!(values, (0..50).collect::<Vec<_>>());
+
+        values.sort();
+        assert_eq!(values, (0..50).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,451 @@
+use std::cmp::Ordering;
+
+use crate::binary_search_algo::search_traced;
+use crate::tracer::{NoopTracer, TraceEvent, Tracer};
+
+// the `_traced` searches report each position they read as a `TraceEvent::Probe`,
+// count them with a `CountingTracer` to compare the strategies. over a slice
+// that is the element's index, over an integer range its offset from the start
+// of the range. the float ternary search has no positions to report
+
+/// plain binary search, the baseline the other strategies are compared against
+pub fn binary_search<T: Ord>(haystack: &[T], needle: &T) -> Option<usize> {
+    binary_search_traced(haystack, needle, &mut NoopTracer)
+}
+
+pub fn binary_search_traced<T: Ord>(
+    haystack: &[T],
+    needle: &T,
+    tracer: &mut impl Tracer,
+) -> Option<usize> {
+    search_traced(haystack, needle, tracer).ok()
+}
+
+/// exponential (galloping) search over a sorted slice
+pub fn exponential_search<T: Ord>(haystack: &[T], needle: &T) -> Option<usize> {
+    exponential_search_traced(haystack, needle, &mut NoopTracer)
+}
+
+pub fn exponential_search_traced<T: Ord>(
+    haystack: &[T],
+    needle: &T,
+    tracer: &mut impl Tracer,
+) -> Option<usize> {
+    exponential_search_unbounded_traced(|idx| haystack.get(idx), &needle, tracer)
+}
+
+/// exponential search over sorted data of unknown length, e.g. a stream
+/// `get` returns `None` past the end of the data
+///
+/// doubles the bound until it passes the needle, then binary searches
+/// the last doubling, so it costs O(log i) where i is the needle's position
+pub fn exponential_search_unbounded<T, F>(get: F, needle: &T) -> Option<usize>
+where
+    T: Ord,
+    F: FnMut(usize) -> Option<T>,
+{
+    exponential_search_unbounded_traced(get, needle, &mut NoopTracer)
+}
+
+pub fn exponential_search_unbounded_traced<T, F>(
+    mut get: F,
+    needle: &T,
+    tracer: &mut impl Tracer,
+) -> Option<usize>
+where
+    T: Ord,
+    F: FnMut(usize) -> Option<T>,
+{
+    // past the end behaves like a value bigger than anything
+    let mut compare = |idx: usize| {
+        tracer.trace(TraceEvent::Probe(idx));
+        get(idx).map_or(Ordering::Greater, |value| value.cmp(needle))
+    };
+
+    let mut bound = 1;
+    while compare(bound - 1) == Ordering::Less {
+        bound *= 2;
+    }
+
+    let mut low = bound / 2;
+    let mut high = bound;
+
+    while low < high {
+        let middle = low + (high - low) / 2;
+
+        match compare(middle) {
+            Ordering::Equal => return Some(middle),
+            Ordering::Greater => high = middle,
+            Ordering::Less => low = middle + 1,
+        }
+    }
+
+    None
+}
+
+/// interpolation search, guesses the position from the key's value
+/// O(log log n) on uniformly distributed keys, O(n) in the worst case
+pub fn interpolation_search(haystack: &[i64], needle: &i64) -> Option<usize> {
+    interpolation_search_traced(haystack, needle, &mut NoopTracer)
+}
+
+pub fn interpolation_search_traced(
+    haystack: &[i64],
+    needle: &i64,
+    tracer: &mut impl Tracer,
+) -> Option<usize> {
+    let needle = *needle;
+
+    if haystack.is_empty() {
+        return None;
+    }
+
+    let mut low = 0;
+    let mut high = haystack.len() - 1;
+
+    while low <= high {
+        tracer.trace(TraceEvent::Probe(low));
+        tracer.trace(TraceEvent::Probe(high));
+        let (lo_value, hi_value) = (haystack[low], haystack[high]);
+
+        if needle < lo_value || needle > hi_value {
+            break;
+        }
+
+        if lo_value == hi_value {
+            return Some(low);
+        }
+
+        // widened so the product can't overflow for extreme keys
+        let offset = (needle as i128 - lo_value as i128) * (high - low) as i128
+            / (hi_value as i128 - lo_value as i128);
+        let guess = low + offset as usize;
+
+        tracer.trace(TraceEvent::Probe(guess));
+        match haystack[guess].cmp(&needle) {
+            Ordering::Equal => return Some(guess),
+            Ordering::Less => low = guess + 1,
+            Ordering::Greater if guess == 0 => break,
+            Ordering::Greater => high = guess - 1,
+        }
+    }
+
+    None
+}
+
+/// jump search, the generic form of the two crystal balls idea:
+/// jump ahead sqrt(n) at a time, then walk the block that passed the needle
+pub fn jump_search<T: Ord>(haystack: &[T], needle: &T) -> Option<usize> {
+    jump_search_traced(haystack, needle, &mut NoopTracer)
+}
+
+pub fn jump_search_traced<T: Ord>(
+    haystack: &[T],
+    needle: &T,
+    tracer: &mut impl Tracer,
+) -> Option<usize> {
+    let len = haystack.len();
+    let jump_amount = (len as f64).sqrt().floor().max(1.0) as usize;
+
+    // first pass finds the block whose last element is not less than the needle
+    let mut block_start = 0;
+    while block_start < len {
+        let block_end = (block_start + jump_amount).min(len);
+
+        tracer.trace(TraceEvent::Probe(block_end - 1));
+        if haystack[block_end - 1] >= *needle {
+            break;
+        }
+
+        block_start = block_end;
+    }
+
+    // second pass walks that block
+    let block_end = (block_start + jump_amount).min(len);
+    for (offset, value) in haystack[block_start..block_end].iter().enumerate() {
+        tracer.trace(TraceEvent::Probe(block_start + offset));
+
+        match value.cmp(needle) {
+            Ordering::Equal => return Some(block_start + offset),
+            Ordering::Greater => break,
+            Ordering::Less => {}
+        }
+    }
+
+    None
+}
+
+/// position of the maximum of `f` on `[low, high]`, where `f` strictly
+/// increases and then strictly decreases
+pub fn ternary_search_max<V, F>(low: i64, high: i64, f: F) -> i64
+where
+    V: PartialOrd,
+    F: FnMut(i64) -> V,
+{
+    ternary_search_max_traced(low, high, f, &mut NoopTracer)
+}
+
+/// same as `ternary_search_max`, every evaluation of `f` at `x` is a probe at
+/// `x - low`, the index `x` would have in a slice holding the range
+pub fn ternary_search_max_traced<V, F>(
+    mut low: i64,
+    mut high: i64,
+    mut f: F,
+    tracer: &mut impl Tracer,
+) -> i64
+where
+    V: PartialOrd,
+    F: FnMut(i64) -> V,
+{
+    assert!(low <= high, "search range must not be empty");
+
+    let start = low;
+    let mut eval = |x: i64| {
+        tracer.trace(TraceEvent::Probe(x.abs_diff(start) as usize));
+        f(x)
+    };
+
+    // measured unsigned, `high - low` overflows for ranges wider than `i64::MAX`
+    while high.abs_diff(low) > 2 {
+        // at most a third of `u64::MAX`, so it fits back in an `i64`
+        let third = (high.abs_diff(low) / 3) as i64;
+        let m1 = low + third;
+        let m2 = high - third;
+
+        if eval(m1) < eval(m2) {
+            low = m1 + 1;
+        } else {
+            high = m2 - 1;
+        }
+    }
+
+    // at most three candidates left
+    let mut best = low;
+    let mut best_value = eval(low);
+
+    for x in (low..=high).skip(1) {
+        let value = eval(x);
+
+        if value > best_value {
+            best = x;
+            best_value = value;
+        }
+    }
+
+    best
+}
+
+/// position of the maximum of a unimodal `f` on `[low, high]` over the reals
+/// each iteration shrinks the interval to two thirds of its size, at the
+/// cost of two evaluations of `f`
+pub fn ternary_search_max_f64<F>(mut low: f64, mut high: f64, iterations: usize, mut f: F) -> f64
+where
+    F: FnMut(f64) -> f64,
+{
+    for _ in 0..iterations {
+        let third = (high - low) / 3.0;
+        let m1 = low + third;
+        let m2 = high - third;
+
+        if f(m1) < f(m2) {
+            low = m1;
+        } else {
+            high = m2;
+        }
+    }
+
+    low + (high - low) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::tracer::{CountingTracer, RecordingTracer};
+
+    type SortedSearch = fn(&[i64], &i64, &mut CountingTracer) -> Option<usize>;
+
+    const SEARCHES: [(&str, SortedSearch); 4] = [
+        ("binary", binary_search_traced::<i64>),
+        ("exponential", exponential_search_traced::<i64>),
+        ("interpolation", interpolation_search_traced),
+        ("jump", jump_search_traced::<i64>),
+    ];
+
+    // runs `search` and returns what it found along with how many probes it took
+    fn probed(search: SortedSearch, haystack: &[i64], needle: i64) -> (Option<usize>, usize) {
+        let mut tracer = CountingTracer::default();
+        let found = search(haystack, &needle, &mut tracer);
+        (found, tracer.probes)
+    }
+
+    fn check_result(name: &str, haystack: &[i64], needle: i64, found: Option<usize>) {
+        match found {
+            Some(idx) => assert_eq!(haystack[idx], needle, "{name} found the wrong element"),
+            None => assert!(
+                haystack.binary_search(&needle).is_err(),
+                "{name} missed {needle} in {haystack:?}"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_shared_fixed_cases() {
+        let cases: [&[i64]; 6] = [
+            &[],
+            &[42],
+            &[1, 2, 3, 4, 5],
+            &[1, 3, 5, 7, 9, 11, 13, 15, 17, 19],
+            &[2, 2, 2, 2, 2],
+            &[-100, -50, 0, 0, 7, 1000, i64::MAX],
+        ];
+
+        for (name, search) in SEARCHES {
+            for haystack in cases {
+                for needle in [-101, -100, 0, 1, 2, 5, 6, 19, 42, 1000, i64::MAX] {
+                    check_result(name, haystack, needle, probed(search, haystack, needle).0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_shared_random_cases() {
+        let mut rng = Rng::new(34);
+
+        for (name, search) in SEARCHES {
+            for len in 0..100 {
+                let mut haystack: Vec<i64> = (0..len).map(|_| rng.range_i64(-500..500)).collect();
+                haystack.sort();
+
+                for _ in 0..20 {
+                    let needle = rng.range_i64(-510..510);
+                    check_result(name, &haystack, needle, probed(search, &haystack, needle).0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_untraced_searches_agree() {
+        let haystack: Vec<i64> = (0..200).map(|x| x * 2).collect();
+
+        for needle in -1..401 {
+            let expected = probed(binary_search_traced, &haystack, needle).0;
+
+            assert_eq!(binary_search(&haystack, &needle), expected);
+            assert_eq!(exponential_search(&haystack, &needle), expected);
+            assert_eq!(interpolation_search(&haystack, &needle), expected);
+            assert_eq!(jump_search(&haystack, &needle), expected);
+        }
+    }
+
+    #[test]
+    fn test_probe_counts() {
+        let len: usize = 1 << 16;
+        let haystack: Vec<i64> = (0..len as i64).map(|x| x * 3).collect();
+
+        for (name, search) in SEARCHES {
+            let mut max = 0;
+
+            for needle in (0..len as i64 * 3).step_by(97) {
+                let (found, probes) = probed(search, &haystack, needle);
+                check_result(name, &haystack, needle, found);
+
+                max = max.max(probes);
+            }
+
+            let log2 = len.ilog2() as usize;
+            match name {
+                "binary" => assert!(max <= log2 + 1),
+                "exponential" => assert!(max <= 2 * log2 + 2),
+                // uniform keys are its best case
+                "interpolation" => assert!(max <= 6),
+                "jump" => assert!(max <= 2 * 256 + 1),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_exponential_search_is_cheap_near_the_front() {
+        let haystack: Vec<i64> = (0..1_000_000).collect();
+
+        let mut near = CountingTracer::default();
+        let mut far = CountingTracer::default();
+
+        assert_eq!(exponential_search_traced(&haystack, &3, &mut near), Some(3));
+        assert_eq!(
+            exponential_search_traced(&haystack, &999_000, &mut far),
+            Some(999_000)
+        );
+        assert!(near.probes < 6);
+        assert!(far.probes > near.probes);
+    }
+
+    #[test]
+    fn test_exponential_search_on_a_stream() {
+        // an endless sorted sequence of squares
+        let found = exponential_search_unbounded(|i| Some(i * i), &(1234 * 1234));
+        assert_eq!(found, Some(1234));
+
+        let missing = exponential_search_unbounded(|i| Some(i * i), &50);
+        assert_eq!(missing, None);
+
+        // a finite stream that ends before the needle
+        let short = exponential_search_unbounded(|i| (i < 10).then_some(i), &20);
+        assert_eq!(short, None);
+    }
+
+    #[test]
+    fn test_ternary_search_max() {
+        let mut tracer = CountingTracer::default();
+        let peak = ternary_search_max_traced(-1000, 1000, |x| -(x - 137) * (x - 137), &mut tracer);
+        assert_eq!(peak, 137);
+        assert!(tracer.probes < 50);
+
+        // peak at either edge
+        assert_eq!(ternary_search_max(0, 100, |x| x), 100);
+        assert_eq!(ternary_search_max(0, 100, |x| -x), 0);
+        assert_eq!(ternary_search_max(5, 5, |x| x), 5);
+
+        // unimodal array
+        let mountain = [1, 3, 8, 12, 17, 20, 19, 4, 2];
+        let found = ternary_search_max(0, mountain.len() as i64 - 1, |i| mountain[i as usize]);
+        assert_eq!(found, 5);
+    }
+
+    #[test]
+    fn test_ternary_search_max_over_all_of_i64() {
+        let peak = ternary_search_max(i64::MIN, i64::MAX, |x| -(x as i128 - 5).abs());
+        assert_eq!(peak, 5);
+
+        assert_eq!(ternary_search_max(i64::MIN, i64::MAX, |x| x), i64::MAX);
+        assert_eq!(
+            ternary_search_max(i64::MIN, i64::MAX, std::cmp::Reverse),
+            i64::MIN
+        );
+        assert_eq!(ternary_search_max(i64::MAX - 1, i64::MAX, |x| x), i64::MAX);
+
+        // probes are offsets from `low`, so they fit a usize even here
+        let mut recorder = RecordingTracer::default();
+        ternary_search_max_traced(i64::MIN, i64::MAX, |x| x, &mut recorder);
+        assert!(
+            recorder
+                .events
+                .contains(&TraceEvent::Probe(u64::MAX as usize))
+        );
+    }
+
+    #[test]
+    fn test_ternary_search_max_f64() {
+        let mut evaluations = 0;
+        let found = ternary_search_max_f64(0.0, std::f64::consts::PI, 100, |x| {
+            evaluations += 1;
+            x.sin()
+        });
+
+        assert!((found - std::f64::consts::FRAC_PI_2).abs() < 1e-6);
+        assert_eq!(evaluations, 200);
+    }
+}
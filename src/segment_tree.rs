@@ -0,0 +1,416 @@
+use std::ops::Range;
+
+/// segment tree over any associative `combine` with point updates
+/// `identity` must satisfy `combine(identity, x) == x == combine(x, identity)`
+pub struct SegmentTree<T> {
+    len: usize,
+    // leaves live in `tree[len..]`, node `i` covers its children `2i` and `2i + 1`
+    tree: Vec<T>,
+    identity: T,
+    combine: fn(&T, &T) -> T,
+}
+
+impl<T: Clone> SegmentTree<T> {
+    pub fn new(values: &[T], identity: T, combine: fn(&T, &T) -> T) -> Self {
+        let len = values.len();
+        let mut tree = vec![identity.clone(); len];
+        tree.extend_from_slice(values);
+
+        for i in (1..len).rev() {
+            tree[i] = combine(&tree[2 * i], &tree[2 * i + 1]);
+        }
+
+        SegmentTree {
+            len,
+            tree,
+            identity,
+            combine,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, idx: usize) -> &T {
+        &self.tree[self.len + idx]
+    }
+
+    /// replaces the value at `idx` and recomputes its ancestors
+    pub fn update(&mut self, idx: usize, value: T) {
+        assert!(idx < self.len, "index out of bounds");
+
+        let mut node = self.len + idx;
+        self.tree[node] = value;
+
+        while node > 1 {
+            node /= 2;
+            self.tree[node] = (self.combine)(&self.tree[2 * node], &self.tree[2 * node + 1]);
+        }
+    }
+
+    /// combines every value in `range`, the identity for an empty range
+    pub fn query(&self, range: Range<usize>) -> T {
+        assert!(range.end <= self.len, "range out of bounds");
+
+        // the two sides are accumulated separately so that
+        // non-commutative operations keep their left to right order
+        let mut left = self.identity.clone();
+        let mut right = self.identity.clone();
+        let mut lo = range.start + self.len;
+        let mut hi = range.end + self.len;
+
+        while lo < hi {
+            if lo % 2 == 1 {
+                left = (self.combine)(&left, &self.tree[lo]);
+                lo += 1;
+            }
+
+            if hi % 2 == 1 {
+                hi -= 1;
+                right = (self.combine)(&self.tree[hi], &right);
+            }
+
+            lo /= 2;
+            hi /= 2;
+        }
+
+        (self.combine)(&left, &right)
+    }
+}
+
+impl SegmentTree<i64> {
+    pub fn sum(values: &[i64]) -> Self {
+        Self::new(values, 0, |a, b| a + b)
+    }
+
+    pub fn min(values: &[i64]) -> Self {
+        Self::new(values, i64::MAX, |a, b| *a.min(b))
+    }
+
+    pub fn max(values: &[i64]) -> Self {
+        Self::new(values, i64::MIN, |a, b| *a.max(b))
+    }
+}
+
+/// segment tree with lazy propagation, so whole ranges can be updated in O(log n)
+///
+/// `apply(value, update, len)` applies a pending update to the aggregate of `len` elements
+/// `compose(older, newer)` merges two pending updates into one
+pub struct LazySegmentTree<T, U> {
+    len: usize,
+    tree: Vec<T>,
+    lazy: Vec<Option<U>>,
+    identity: T,
+    combine: fn(&T, &T) -> T,
+    apply: fn(&T, &U, usize) -> T,
+    compose: fn(&U, &U) -> U,
+}
+
+impl<T: Clone, U: Clone> LazySegmentTree<T, U> {
+    pub fn new(
+        values: &[T],
+        identity: T,
+        combine: fn(&T, &T) -> T,
+        apply: fn(&T, &U, usize) -> T,
+        compose: fn(&U, &U) -> U,
+    ) -> Self {
+        let len = values.len();
+        let size = 4 * len.max(1);
+
+        let mut tree = LazySegmentTree {
+            len,
+            tree: vec![identity.clone(); size],
+            lazy: vec![None; size],
+            identity,
+            combine,
+            apply,
+            compose,
+        };
+
+        if len > 0 {
+            tree.build(values, 1, 0, len);
+        }
+
+        tree
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn build(&mut self, values: &[T], node: usize, lo: usize, hi: usize) {
+        if hi - lo == 1 {
+            self.tree[node] = values[lo].clone();
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        self.build(values, 2 * node, lo, mid);
+        self.build(values, 2 * node + 1, mid, hi);
+        self.tree[node] = (self.combine)(&self.tree[2 * node], &self.tree[2 * node + 1]);
+    }
+
+    /// applies an update to the node and remembers it for its children
+    fn apply_to(&mut self, node: usize, update: &U, len: usize) {
+        self.tree[node] = (self.apply)(&self.tree[node], update, len);
+        self.lazy[node] = Some(match &self.lazy[node] {
+            Some(pending) => (self.compose)(pending, update),
+            None => update.clone(),
+        });
+    }
+
+    /// hands a pending update down to both children
+    fn push_down(&mut self, node: usize, lo: usize, mid: usize, hi: usize) {
+        if let Some(update) = self.lazy[node].take() {
+            self.apply_to(2 * node, &update, mid - lo);
+            self.apply_to(2 * node + 1, &update, hi - mid);
+        }
+    }
+
+    /// applies `update` to every element in `range`
+    pub fn update(&mut self, range: Range<usize>, update: U) {
+        assert!(range.end <= self.len, "range out of bounds");
+
+        if range.start < range.end {
+            self.update_node(1, 0, self.len, &range, &update);
+        }
+    }
+
+    fn update_node(&mut self, node: usize, lo: usize, hi: usize, range: &Range<usize>, update: &U) {
+        if range.end <= lo || hi <= range.start {
+            return;
+        }
+
+        if range.start <= lo && hi <= range.end {
+            self.apply_to(node, update, hi - lo);
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        self.push_down(node, lo, mid, hi);
+        self.update_node(2 * node, lo, mid, range, update);
+        self.update_node(2 * node + 1, mid, hi, range, update);
+        self.tree[node] = (self.combine)(&self.tree[2 * node], &self.tree[2 * node + 1]);
+    }
+
+    /// combines every value in `range`, the identity for an empty range
+    pub fn query(&mut self, range: Range<usize>) -> T {
+        assert!(range.end <= self.len, "range out of bounds");
+
+        if range.start >= range.end {
+            return self.identity.clone();
+        }
+
+        self.query_node(1, 0, self.len, &range)
+    }
+
+    fn query_node(&mut self, node: usize, lo: usize, hi: usize, range: &Range<usize>) -> T {
+        if range.end <= lo || hi <= range.start {
+            return self.identity.clone();
+        }
+
+        if range.start <= lo && hi <= range.end {
+            return self.tree[node].clone();
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        self.push_down(node, lo, mid, hi);
+        let left = self.query_node(2 * node, lo, mid, range);
+        let right = self.query_node(2 * node + 1, mid, hi, range);
+        (self.combine)(&left, &right)
+    }
+}
+
+impl LazySegmentTree<i64, i64> {
+    /// range add, range sum
+    pub fn add_sum(values: &[i64]) -> Self {
+        Self::new(
+            values,
+            0,
+            |a, b| a + b,
+            |sum, add, len| sum + add * len as i64,
+            |a, b| a + b,
+        )
+    }
+
+    /// range add, range min
+    pub fn add_min(values: &[i64]) -> Self {
+        Self::new(
+            values,
+            i64::MAX,
+            |a, b| *a.min(b),
+            |min, add, _| min.saturating_add(*add),
+            |a, b| a + b,
+        )
+    }
+
+    /// range add, range max
+    pub fn add_max(values: &[i64]) -> Self {
+        Self::new(
+            values,
+            i64::MIN,
+            |a, b| *a.max(b),
+            |max, add, _| max.saturating_add(*add),
+            |a, b| a + b,
+        )
+    }
+
+    /// range assign, range sum
+    pub fn assign_sum(values: &[i64]) -> Self {
+        Self::new(
+            values,
+            0,
+            |a, b| a + b,
+            |_, value, len| value * len as i64,
+            |_, newer| *newer,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    fn random_values(rng: &mut Rng, len: usize) -> Vec<i64> {
+        (0..len).map(|_| rng.range_i64(-1000..1000)).collect()
+    }
+
+    fn random_range(rng: &mut Rng, len: usize) -> Range<usize> {
+        let a = rng.range_usize(0..len + 1);
+        let b = rng.range_usize(0..len + 1);
+        a.min(b)..a.max(b)
+    }
+
+    #[test]
+    fn test_sum_min_max_against_brute_force() {
+        let mut rng = Rng::new(30);
+
+        for len in 1..60 {
+            let mut values = random_values(&mut rng, len);
+            let mut sum = SegmentTree::sum(&values);
+            let mut min = SegmentTree::min(&values);
+            let mut max = SegmentTree::max(&values);
+            assert_eq!(sum.len(), len);
+
+            for _ in 0..100 {
+                let range = random_range(&mut rng, len);
+                let slice = &values[range.clone()];
+
+                assert_eq!(sum.query(range.clone()), slice.iter().sum::<i64>());
+                assert_eq!(
+                    min.query(range.clone()),
+                    slice.iter().copied().min().unwrap_or(i64::MAX)
+                );
+                assert_eq!(
+                    max.query(range),
+                    slice.iter().copied().max().unwrap_or(i64::MIN)
+                );
+
+                let idx = rng.range_usize(0..len);
+                let value = rng.range_i64(-1000..1000);
+                values[idx] = value;
+                sum.update(idx, value);
+                min.update(idx, value);
+                max.update(idx, value);
+                assert_eq!(*sum.get(idx), value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_commutative_combine() {
+        // string concatenation is associative but not commutative
+        let words: Vec<String> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut tree = SegmentTree::new(&words, String::new(), |a, b| format!("{a}{b}"));
+
+        assert_eq!(tree.query(0..5), "abcde");
+        assert_eq!(tree.query(1..4), "bcd");
+        assert_eq!(tree.query(2..2), "");
+
+        tree.update(2, "X".to_string());
+        assert_eq!(tree.query(0..5), "abXde");
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let tree = SegmentTree::sum(&[]);
+        assert!(tree.is_empty());
+        assert_eq!(tree.query(0..0), 0);
+
+        let mut lazy = LazySegmentTree::add_sum(&[]);
+        assert!(lazy.is_empty());
+        assert_eq!(lazy.query(0..0), 0);
+    }
+
+    #[test]
+    fn test_lazy_range_add_against_brute_force() {
+        let mut rng = Rng::new(31);
+
+        for len in 1..50 {
+            let mut values = random_values(&mut rng, len);
+            let mut sum = LazySegmentTree::add_sum(&values);
+            let mut min = LazySegmentTree::add_min(&values);
+            let mut max = LazySegmentTree::add_max(&values);
+            assert_eq!(sum.len(), len);
+
+            for _ in 0..100 {
+                let range = random_range(&mut rng, len);
+                let add = rng.range_i64(-100..100);
+
+                for value in &mut values[range.clone()] {
+                    *value += add;
+                }
+                sum.update(range.clone(), add);
+                min.update(range.clone(), add);
+                max.update(range, add);
+
+                let range = random_range(&mut rng, len);
+                let slice = &values[range.clone()];
+
+                assert_eq!(sum.query(range.clone()), slice.iter().sum::<i64>());
+                assert_eq!(
+                    min.query(range.clone()),
+                    slice.iter().copied().min().unwrap_or(i64::MAX)
+                );
+                assert_eq!(
+                    max.query(range),
+                    slice.iter().copied().max().unwrap_or(i64::MIN)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lazy_range_assign_against_brute_force() {
+        let mut rng = Rng::new(32);
+
+        for len in 1..50 {
+            let mut values = random_values(&mut rng, len);
+            let mut tree = LazySegmentTree::assign_sum(&values);
+
+            for _ in 0..100 {
+                let range = random_range(&mut rng, len);
+                let value = rng.range_i64(-100..100);
+
+                values[range.clone()].fill(value);
+                tree.update(range, value);
+
+                let range = random_range(&mut rng, len);
+                assert_eq!(tree.query(range.clone()), values[range].iter().sum::<i64>());
+            }
+        }
+    }
+}
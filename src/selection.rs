@@ -0,0 +1,435 @@
+use std::cmp::Ordering;
+
+use crate::elementary_sort::insertion_sort_by;
+use crate::min_heap::sift_down;
+use crate::quicksort::{intro_sort_by, partition};
+use crate::rng::Rng;
+use crate::tracer::NoopTracer;
+
+// median of medians works on groups this big
+const GROUP: usize = 5;
+
+// ranges this short or shorter are simply insertion sorted
+const SMALL_LEN: usize = 5;
+
+// quickselect's pivots are random but seeded, so runs are repeatable
+const SEED: u64 = 0x5e1ec7;
+
+/// the `k`-th smallest element (0-based), expected O(n) with random pivots
+///
+/// leaves `arr` partitioned around it like `nth_element`, panics if `k` is
+/// out of bounds
+///
+/// the seed is fixed, so the pivots are the same on every run and input built
+/// against them can make it quadratic, use `nth_element` on untrusted input
+pub fn quickselect<T: Ord>(arr: &mut [T], k: usize) -> &T {
+    quickselect_by(arr, k, T::cmp)
+}
+
+pub fn quickselect_by<T, F>(arr: &mut [T], k: usize, mut compare: F) -> &T
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    select(arr, k, &mut compare, usize::MAX)
+}
+
+/// the `k`-th smallest element in worst-case O(n), pivoting on the median of
+/// the medians of groups of five
+///
+/// slower than `quickselect` on typical input, but no input can make it quadratic
+pub fn median_of_medians<T: Ord>(arr: &mut [T], k: usize) -> &T {
+    median_of_medians_by(arr, k, T::cmp)
+}
+
+pub fn median_of_medians_by<T, F>(arr: &mut [T], k: usize, mut compare: F) -> &T
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    select(arr, k, &mut compare, 0)
+}
+
+/// reorders `arr` so `arr[k]` is what a full sort would put there, nothing
+/// before it is greater and nothing after it is smaller
+///
+/// introselect: random pivots for 2·log n rounds, then median of medians if
+/// the range still hasn't narrowed down, so worst-case O(n)
+pub fn nth_element<T: Ord>(arr: &mut [T], k: usize) {
+    nth_element_by(arr, k, T::cmp);
+}
+
+pub fn nth_element_by<T, F>(arr: &mut [T], k: usize, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len: usize = arr.len();
+    select(arr, k, &mut compare, 2 * len.max(1).ilog2() as usize);
+}
+
+/// sorts only the `k` smallest elements into `arr[..k]`, the rest end up in
+/// no particular order after them
+///
+/// O(n + k log k), `k` past the end sorts everything
+pub fn partial_sort<T: Ord>(arr: &mut [T], k: usize) {
+    partial_sort_by(arr, k, T::cmp);
+}
+
+pub fn partial_sort_by<T, F>(arr: &mut [T], k: usize, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let k = k.min(arr.len());
+    if k == 0 {
+        return;
+    }
+
+    if k < arr.len() {
+        nth_element_by(arr, k - 1, &mut compare);
+    }
+    intro_sort_by(&mut arr[..k], compare);
+}
+
+/// the `k` largest items, largest first, fewer if there aren't `k` of them
+///
+/// streams through `items` keeping only a min-heap of the best `k` so far,
+/// so O(n log k) time and O(k) memory however long the input is
+pub fn top_k<T: Ord, I: IntoIterator<Item = T>>(items: I, k: usize) -> Vec<T> {
+    top_k_by(items, k, T::cmp)
+}
+
+pub fn top_k_by<T, I, F>(items: I, k: usize, mut compare: F) -> Vec<T>
+where
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+
+    let mut items = items.into_iter();
+    let mut heap: Vec<T> = items.by_ref().take(k).collect();
+
+    for idx in (0..heap.len() / 2).rev() {
+        sift_down(&mut heap, idx, &mut compare, &mut NoopTracer);
+    }
+
+    // the root is the smallest of the best so far, anything bigger replaces it
+    for item in items {
+        if compare(&item, &heap[0]) == Ordering::Greater {
+            heap[0] = item;
+            sift_down(&mut heap, 0, &mut compare, &mut NoopTracer);
+        }
+    }
+
+    intro_sort_by(&mut heap, |a, b| compare(b, a));
+    heap
+}
+
+fn select<'a, T, F>(arr: &'a mut [T], k: usize, compare: &mut F, random_rounds: usize) -> &'a T
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    assert!(
+        k < arr.len(),
+        "k is {k} but there are only {} elements",
+        arr.len()
+    );
+
+    let mut selector = Selector {
+        compare,
+        rng: Rng::new(SEED),
+        random_rounds,
+    };
+    selector.select(arr, 0, arr.len() - 1, k);
+
+    &arr[k]
+}
+
+/// the state one selection threads through its rounds
+struct Selector<'a, F> {
+    compare: &'a mut F,
+    rng: Rng,
+    // random pivots left before switching to median of medians
+    random_rounds: usize,
+}
+
+impl<F> Selector<'_, F> {
+    // puts the element that belongs at `k` there, within the inclusive range `lo..=hi`
+    fn select<T>(&mut self, arr: &mut [T], mut lo: usize, mut hi: usize, k: usize)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        while lo < hi {
+            if hi - lo < SMALL_LEN {
+                insertion_sort_by(&mut arr[lo..=hi], &mut *self.compare);
+                return;
+            }
+
+            let pivot = if self.random_rounds > 0 {
+                self.random_rounds -= 1;
+                self.rng.range_usize(lo..hi + 1)
+            } else {
+                self.median_of_medians(arr, lo, hi)
+            };
+
+            arr.swap(pivot, hi);
+            let idx = partition(arr, lo, hi, &mut *self.compare, &mut NoopTracer);
+
+            match k.cmp(&idx) {
+                Ordering::Equal => return,
+                Ordering::Greater => lo = idx + 1,
+                Ordering::Less => {
+                    // copies of the pivot all land on its left, so pull them
+                    // next to it, otherwise a run of duplicates would only
+                    // shrink the range by one a round
+                    let equal_start = self.gather_equal(arr, lo, idx);
+                    if k >= equal_start {
+                        return;
+                    }
+                    hi = equal_start - 1;
+                }
+            }
+        }
+    }
+
+    // moves the elements of `lo..pivot` equal to `arr[pivot]` up against it,
+    // all of them are known to be no greater, returns where the equal block starts
+    fn gather_equal<T>(&mut self, arr: &mut [T], lo: usize, pivot: usize) -> usize
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut equal_start = pivot;
+
+        for i in (lo..pivot).rev() {
+            if (self.compare)(&arr[i], &arr[pivot]) != Ordering::Less {
+                equal_start -= 1;
+                arr.swap(i, equal_start);
+            }
+        }
+
+        equal_start
+    }
+
+    // sorts each group of five, gathers their medians at the front of the
+    // range and selects the median of those, returns where it ended up
+    fn median_of_medians<T>(&mut self, arr: &mut [T], lo: usize, hi: usize) -> usize
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut medians_end = lo;
+
+        for start in (lo..=hi).step_by(GROUP) {
+            let end = (start + GROUP - 1).min(hi);
+            insertion_sort_by(&mut arr[start..=end], &mut *self.compare);
+            arr.swap(medians_end, start + (end - start) / 2);
+            medians_end += 1;
+        }
+
+        let mid = lo + (medians_end - 1 - lo) / 2;
+        self.select(arr, lo, medians_end - 1, mid);
+        mid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Select = fn(&mut [i32], usize) -> i32;
+
+    const SELECTS: [(&str, Select); 3] = [
+        ("quickselect", |arr, k| *quickselect(arr, k)),
+        ("median_of_medians", |arr, k| *median_of_medians(arr, k)),
+        ("nth_element", |arr, k| {
+            nth_element(arr, k);
+            arr[k]
+        }),
+    ];
+
+    fn inputs() -> Vec<Vec<i32>> {
+        let mut rng = Rng::new(49);
+        let mut inputs = vec![
+            vec![7],
+            vec![2, 1],
+            vec![5; 40],
+            (0..60).collect(),
+            (0..60).rev().collect(),
+            // organ pipe
+            (0..30).chain((0..30).rev()).collect(),
+        ];
+        for len in [3, 6, 11, 50, 200] {
+            inputs.push(rng.vec_i32(len, -1000..1000));
+            inputs.push(rng.vec_i32(len, 0..4));
+        }
+        inputs
+    }
+
+    fn count_compares(arr: &mut [i32], k: usize, random_rounds: usize) -> usize {
+        let mut compares = 0;
+        select(
+            arr,
+            k,
+            &mut |a: &i32, b: &i32| {
+                compares += 1;
+                a.cmp(b)
+            },
+            random_rounds,
+        );
+        compares
+    }
+
+    #[test]
+    fn test_selects_match_full_sort() {
+        for input in inputs() {
+            let mut sorted = input.clone();
+            sorted.sort();
+
+            for (name, select) in SELECTS {
+                for k in 0..input.len() {
+                    let mut arr = input.clone();
+                    assert_eq!(select(&mut arr, k), sorted[k], "{name} k={k} on {input:?}");
+
+                    // partitioned around `k`, and still the same elements
+                    assert!(arr[..k].iter().all(|&x| x <= arr[k]), "{name} k={k}");
+                    assert!(arr[k + 1..].iter().all(|&x| x >= arr[k]), "{name} k={k}");
+                    arr.sort();
+                    assert_eq!(arr, sorted);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_by_key() {
+        let mut words = ["pear", "fig", "banana", "kiwi", "apple"];
+        assert_eq!(
+            *quickselect_by(&mut words, 0, |a, b| a.len().cmp(&b.len())),
+            "fig"
+        );
+        assert_eq!(
+            *median_of_medians_by(&mut words, 0, |a, b| b.len().cmp(&a.len())),
+            "banana"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "only 3 elements")]
+    fn test_k_out_of_bounds() {
+        quickselect(&mut [1, 2, 3], 3);
+    }
+
+    #[test]
+    fn test_median_of_medians_is_linear() {
+        let len = 10_000;
+        let inputs: [Vec<i32>; 4] = [
+            (0..len).collect(),
+            (0..len).rev().collect(),
+            vec![3; len as usize],
+            Rng::new(490).vec_i32(len as usize, 0..100),
+        ];
+
+        for input in inputs {
+            for k in [0, len as usize / 2, len as usize - 1] {
+                let compares = count_compares(&mut input.clone(), k, 0);
+                assert!(
+                    compares < 25 * len as usize,
+                    "{compares} compares for k={k}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_duplicates_stay_linear() {
+        // every pivot is a duplicate, without gathering them this is quadratic
+        let mut same = vec![1; 10_000];
+        assert!(count_compares(&mut same, 0, usize::MAX) < 30_000);
+    }
+
+    #[test]
+    fn test_partial_sort() {
+        let mut rng = Rng::new(491);
+
+        for len in [0, 1, 5, 64, 300] {
+            let input = rng.vec_i32(len, 0..50);
+            let mut sorted = input.clone();
+            sorted.sort();
+
+            for k in [0, 1, len / 3, len, len + 5] {
+                let mut arr = input.clone();
+                partial_sort(&mut arr, k);
+
+                let k = k.min(len);
+                assert_eq!(arr[..k], sorted[..k], "k={k} on {input:?}");
+                arr.sort();
+                assert_eq!(arr, sorted);
+            }
+        }
+
+        let mut words = vec!["d", "a", "c", "b"];
+        partial_sort_by(&mut words, 2, |a, b| b.cmp(a));
+        assert_eq!(words[..2], ["d", "c"]);
+    }
+
+    #[test]
+    fn test_top_k() {
+        let mut rng = Rng::new(492);
+
+        for len in [0, 1, 10, 500] {
+            let input = rng.vec_i32(len, -100..100);
+            let mut descending = input.clone();
+            descending.sort_by(|a, b| b.cmp(a));
+
+            for k in [0, 1, 3, len / 2, len, len + 1] {
+                let top = top_k(input.iter().copied(), k);
+                assert_eq!(top, descending[..k.min(len)], "k={k}");
+            }
+        }
+
+        // works straight off an iterator, and `_by` can flip it to the smallest
+        assert_eq!(top_k(1..=1_000_000, 3), [1_000_000, 999_999, 999_998]);
+        assert_eq!(top_k_by(1..=100, 2, |a, b| b.cmp(a)), [1, 2]);
+        assert_eq!(
+            top_k_by(["ccc", "a", "bb"], 1, |a, b| a.len().cmp(&b.len())),
+            ["ccc"]
+        );
+    }
+
+    /// run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_selection_against_sorting() {
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        let len = 2_000_000;
+        let input = Rng::new(1).vec_i32(len, i32::MIN..i32::MAX);
+
+        let time = |name: &str, run: &mut dyn FnMut()| {
+            let start = Instant::now();
+            run();
+            println!("  {name:<18} {:?}", start.elapsed());
+        };
+
+        println!("median of {len} random i32");
+        time("quickselect", &mut || {
+            black_box(quickselect(&mut input.clone(), len / 2));
+        });
+        time("median_of_medians", &mut || {
+            black_box(median_of_medians(&mut input.clone(), len / 2));
+        });
+        time("nth_element", &mut || {
+            nth_element(black_box(&mut input.clone()), len / 2)
+        });
+        time("intro_sort", &mut || {
+            intro_sort_by(black_box(&mut input.clone()), i32::cmp)
+        });
+
+        println!("top 100 of {len} random i32");
+        time("top_k", &mut || {
+            black_box(top_k(input.iter().copied(), 100));
+        });
+        time("partial_sort", &mut || {
+            partial_sort(black_box(&mut input.clone()), 100)
+        });
+    }
+}
@@ -0,0 +1,375 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use crate::sorting::{InsertionSort, IntroSort, Sorter, TimSort};
+
+// inputs this short, or this close to sorted, are best left to insertion sort
+const SMALL_LEN: usize = 16;
+const SMALL_DISPLACEMENT: usize = 8;
+
+pub fn is_sorted<T: Ord>(arr: &[T]) -> bool {
+    is_sorted_by(arr, T::cmp)
+}
+
+/// whether no element comes after one that `compare` orders after it
+pub fn is_sorted_by<T, F>(arr: &[T], mut compare: F) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    arr.windows(2)
+        .all(|pair| compare(&pair[0], &pair[1]) != Ordering::Greater)
+}
+
+pub fn count_inversions<T: Ord>(arr: &[T]) -> u64 {
+    count_inversions_by(arr, T::cmp)
+}
+
+/// how many pairs `i < j` have `arr[i]` ordered after `arr[j]`, 0 for sorted
+/// input and `n(n-1)/2` for strictly descending
+///
+/// O(n log n), counted while merge sorting the indices, `arr` is left alone
+pub fn count_inversions_by<T, F>(arr: &[T], mut compare: F) -> u64
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sorted_order(arr, &mut compare).1
+}
+
+/// whether `output` holds exactly the elements of `input`, duplicates
+/// included, in any order
+pub fn is_permutation<T: Ord>(input: &[T], output: &[T]) -> bool {
+    if input.len() != output.len() {
+        return false;
+    }
+
+    let mut counts: BTreeMap<&T, isize> = BTreeMap::new();
+    for value in input {
+        *counts.entry(value).or_default() += 1;
+    }
+    for value in output {
+        *counts.entry(value).or_default() -= 1;
+    }
+
+    counts.values().all(|&count| count == 0)
+}
+
+/// whether `output` is `input` sorted by `compare` with the elements that
+/// compare equal still in their input order
+///
+/// the elements need to be told apart by `==` for it to notice anything, so
+/// sort records carrying more than their key
+///
+/// the expected order comes from std's stable sort rather than one of ours,
+/// so it can check the crate's merge sorts too
+pub fn is_stable_by<T, F>(input: &[T], output: &[T], compare: F) -> bool
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut expected = input.to_vec();
+    expected.sort_by(compare);
+
+    expected == output
+}
+
+/// how far from sorted an input is, for picking what to sort it with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Presortedness {
+    pub len: usize,
+    /// maximal runs that are non-descending or strictly descending, the ones
+    /// timsort and natural merge sort pick up, 1 for sorted or reversed input
+    pub runs: usize,
+    pub inversions: u64,
+    /// furthest any element is from where a stable sort puts it
+    pub max_displacement: usize,
+}
+
+impl Presortedness {
+    pub fn of<T: Ord>(arr: &[T]) -> Self {
+        Self::of_by(arr, T::cmp)
+    }
+
+    pub fn of_by<T, F>(arr: &[T], mut compare: F) -> Self
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let (order, inversions) = sorted_order(arr, &mut compare);
+
+        let max_displacement = order
+            .iter()
+            .enumerate()
+            .map(|(to, &from)| to.abs_diff(from))
+            .max()
+            .unwrap_or(0);
+
+        Presortedness {
+            len: arr.len(),
+            runs: count_runs(arr, &mut compare),
+            inversions,
+            max_displacement,
+        }
+    }
+
+    /// inversions as a share of the most there could be, 0.0 sorted and 1.0
+    /// strictly descending
+    pub fn disorder(&self) -> f64 {
+        let most = self.len as u64 * self.len.saturating_sub(1) as u64 / 2;
+        if most == 0 {
+            0.0
+        } else {
+            self.inversions as f64 / most as f64
+        }
+    }
+
+    /// the sorter best suited to this input
+    ///
+    /// insertion sort when it is short or nothing has far to move, timsort
+    /// when there are long runs either way to merge, introsort otherwise
+    pub fn recommend<T: Clone>(&self) -> Box<dyn Sorter<T>> {
+        if self.len <= SMALL_LEN
+            || self.max_displacement <= SMALL_DISPLACEMENT
+            || self.inversions <= self.len as u64
+        {
+            Box::new(InsertionSort)
+        } else if self.runs <= self.len / SMALL_LEN {
+            Box::new(TimSort)
+        } else {
+            Box::new(IntroSort)
+        }
+    }
+}
+
+// counts runs the way `merge_sort`'s natural variant finds them
+fn count_runs<T, F>(arr: &[T], compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut runs = 0;
+    let mut start = 0;
+
+    while start < arr.len() {
+        let mut end = start + 1;
+
+        if end < arr.len() {
+            // the first pair decides which way the run goes
+            let descending = compare(&arr[end], &arr[start]) == Ordering::Less;
+            end += 1;
+
+            while end < arr.len()
+                && (compare(&arr[end], &arr[end - 1]) == Ordering::Less) == descending
+            {
+                end += 1;
+            }
+        }
+
+        runs += 1;
+        start = end;
+    }
+
+    runs
+}
+
+// indices of `arr` in stably sorted order, and the inversions it took to get there
+fn sorted_order<T, F>(arr: &[T], compare: &mut F) -> (Vec<usize>, u64)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut order: Vec<usize> = (0..arr.len()).collect();
+    let mut scratch = Vec::with_capacity(arr.len());
+    let inversions = merge_counting(arr, &mut order, &mut scratch, compare);
+
+    (order, inversions)
+}
+
+// top-down merge sort of `order` by the elements it points at, counting how
+// many left elements each right one jumps ahead of
+fn merge_counting<T, F>(
+    arr: &[T],
+    order: &mut [usize],
+    scratch: &mut Vec<usize>,
+    compare: &mut F,
+) -> u64
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if order.len() <= 1 {
+        return 0;
+    }
+
+    let mid = order.len() / 2;
+    let mut inversions = merge_counting(arr, &mut order[..mid], scratch, compare)
+        + merge_counting(arr, &mut order[mid..], scratch, compare);
+
+    scratch.clear();
+    scratch.extend_from_slice(&order[..mid]);
+
+    let (mut i, mut j, mut k) = (0, mid, 0);
+    while i < scratch.len() && j < order.len() {
+        // ties go left, which keeps it stable and doesn't count them
+        if compare(&arr[order[j]], &arr[scratch[i]]) == Ordering::Less {
+            inversions += (scratch.len() - i) as u64;
+            order[k] = order[j];
+            j += 1;
+        } else {
+            order[k] = scratch[i];
+            i += 1;
+        }
+        k += 1;
+    }
+    order[k..k + scratch.len() - i].copy_from_slice(&scratch[i..]);
+
+    inversions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    fn brute_force_inversions(arr: &[i32]) -> u64 {
+        let mut inversions = 0;
+        for i in 0..arr.len() {
+            for j in i + 1..arr.len() {
+                if arr[i] > arr[j] {
+                    inversions += 1;
+                }
+            }
+        }
+        inversions
+    }
+
+    #[test]
+    fn test_is_sorted() {
+        assert!(is_sorted::<i32>(&[]));
+        assert!(is_sorted(&[1]));
+        assert!(is_sorted(&[1, 1, 2, 3, 3]));
+        assert!(!is_sorted(&[1, 3, 2]));
+
+        assert!(is_sorted_by(&[3, 2, 2, 1], |a: &i32, b| b.cmp(a)));
+        assert!(is_sorted_by(&["a", "cc", "bb"], |a, b| a
+            .len()
+            .cmp(&b.len())));
+        assert!(!is_sorted_by(&[0.5, f64::NAN, 0.1], f64::total_cmp));
+    }
+
+    #[test]
+    fn test_count_inversions_matches_brute_force() {
+        let mut rng = Rng::new(50);
+
+        for len in 0..80 {
+            let arr = rng.vec_i32(len, 0..10);
+            assert_eq!(
+                count_inversions(&arr),
+                brute_force_inversions(&arr),
+                "{arr:?}"
+            );
+        }
+
+        let len = 10_000u64;
+        let descending: Vec<u64> = (0..len).rev().collect();
+        assert_eq!(count_inversions(&descending), len * (len - 1) / 2);
+        assert_eq!(count_inversions(&vec![7; 100]), 0);
+    }
+
+    #[test]
+    fn test_is_permutation() {
+        assert!(is_permutation::<i32>(&[], &[]));
+        assert!(is_permutation(&[3, 1, 2, 1], &[1, 1, 2, 3]));
+        assert!(!is_permutation(&[3, 1, 2, 1], &[1, 2, 2, 3]));
+        assert!(!is_permutation(&[1, 2], &[1, 2, 2]));
+        assert!(!is_permutation(&["a", "b"], &["a", "c"]));
+    }
+
+    #[test]
+    fn test_is_stable_by() {
+        let input = [(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd')];
+        let by_key = |a: &(i32, char), b: &(i32, char)| a.0.cmp(&b.0);
+
+        assert!(is_stable_by(
+            &input,
+            &[(0, 'b'), (0, 'd'), (1, 'a'), (1, 'c')],
+            by_key
+        ));
+        // sorted, but equal keys swapped
+        assert!(!is_stable_by(
+            &input,
+            &[(0, 'd'), (0, 'b'), (1, 'a'), (1, 'c')],
+            by_key
+        ));
+        // not sorted at all
+        assert!(!is_stable_by(&input, &input, by_key));
+    }
+
+    #[test]
+    fn test_presortedness() {
+        assert_eq!(
+            Presortedness::of::<i32>(&[]),
+            Presortedness {
+                len: 0,
+                runs: 0,
+                inversions: 0,
+                max_displacement: 0,
+            }
+        );
+
+        let report = Presortedness::of(&[1, 2, 3, 4, 5]);
+        assert_eq!(
+            (report.runs, report.inversions, report.max_displacement),
+            (1, 0, 0)
+        );
+        assert_eq!(report.disorder(), 0.0);
+
+        let report = Presortedness::of(&[5, 4, 3, 2, 1]);
+        assert_eq!(
+            (report.runs, report.inversions, report.max_displacement),
+            (1, 10, 4)
+        );
+        assert_eq!(report.disorder(), 1.0);
+
+        // the 9 has to move to the end, everything else one step left
+        let report = Presortedness::of(&[9, 1, 2, 3]);
+        assert_eq!(
+            (report.runs, report.inversions, report.max_displacement),
+            (2, 3, 3)
+        );
+
+        // up, down, up again
+        let report = Presortedness::of(&[1, 2, 3, 9, 8, 7, 4, 5]);
+        assert_eq!(report.runs, 3);
+
+        // equal keys keep their order, so they aren't displaced
+        let report = Presortedness::of_by(&[(1, 'a'), (0, 'b'), (1, 'c')], |a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            (report.runs, report.inversions, report.max_displacement),
+            (2, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_recommend() {
+        let mut rng = Rng::new(500);
+        let recommend = |arr: &[i32]| Presortedness::of(arr).recommend::<i32>().name();
+
+        assert_eq!(recommend(&[3, 1, 2]), "insertion");
+
+        // nothing far from home
+        let mut nearly: Vec<i32> = (0..10_000).collect();
+        for i in (0..nearly.len() - 1).step_by(50) {
+            nearly.swap(i, i + 1);
+        }
+        assert_eq!(recommend(&nearly), "insertion");
+
+        // a few long sorted runs, and the same reversed
+        let mut random: Vec<i32> = (0..10_000).collect();
+        rng.shuffle(&mut random);
+        let mut blocks = random.clone();
+        for block in blocks.chunks_mut(2500) {
+            block.sort();
+        }
+        assert_eq!(recommend(&blocks), "tim");
+        blocks.reverse();
+        assert_eq!(recommend(&blocks), "tim");
+
+        assert_eq!(recommend(&random), "intro");
+    }
+}
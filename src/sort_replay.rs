@@ -0,0 +1,430 @@
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::tracer::{TraceEvent, Tracer, WriteTracer};
+
+/// one recorded step of a sort, writes carry their value so the step can be
+/// redone on a copy of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SortStep<T> {
+    Probe(usize),
+    Compare(usize, usize),
+    Swap(usize, usize),
+    Write(usize, T),
+}
+
+impl<T> SortStep<T> {
+    /// the indices this step looked at or changed
+    pub fn indices(&self) -> Vec<usize> {
+        match *self {
+            SortStep::Probe(idx) | SortStep::Write(idx, _) => vec![idx],
+            SortStep::Compare(a, b) | SortStep::Swap(a, b) => vec![a, b],
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for SortStep<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortStep::Probe(idx) => write!(f, "probe {idx}"),
+            SortStep::Compare(a, b) => write!(f, "compare {a} and {b}"),
+            SortStep::Swap(a, b) => write!(f, "swap {a} and {b}"),
+            SortStep::Write(idx, value) => write!(f, "write {value:?} to {idx}"),
+        }
+    }
+}
+
+/// tracer that keeps a copy of the input along with every step taken on it,
+/// for any of the `_traced` sorts
+#[derive(Debug, Clone)]
+pub struct SortRecorder<T> {
+    initial: Vec<T>,
+    steps: Vec<SortStep<T>>,
+}
+
+impl<T: Clone> SortRecorder<T> {
+    pub fn new(input: &[T]) -> Self {
+        SortRecorder {
+            initial: input.to_vec(),
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn steps(&self) -> &[SortStep<T>] {
+        &self.steps
+    }
+
+    pub fn into_replay(self) -> Replay<T> {
+        Replay {
+            initial: self.initial,
+            steps: self.steps,
+        }
+    }
+}
+
+impl<T> Tracer for SortRecorder<T> {
+    fn trace(&mut self, event: TraceEvent) {
+        let step = match event {
+            TraceEvent::Probe(idx) => SortStep::Probe(idx),
+            TraceEvent::Compare(a, b) => SortStep::Compare(a, b),
+            TraceEvent::Swap(a, b) => SortStep::Swap(a, b),
+        };
+        self.steps.push(step);
+    }
+}
+
+impl<T: Clone> WriteTracer<T> for SortRecorder<T> {
+    fn write(&mut self, idx: usize, value: &T) {
+        self.steps.push(SortStep::Write(idx, value.clone()));
+    }
+}
+
+/// runs `sort` on a copy of `input` with a recorder attached, e.g.
+/// `record(&arr, |arr, tracer| bubble_sort_by_traced(arr, i32::cmp, tracer))`
+pub fn record<T, S>(input: &[T], sort: S) -> Replay<T>
+where
+    T: Clone,
+    S: FnOnce(&mut [T], &mut SortRecorder<T>),
+{
+    let mut recorder = SortRecorder::new(input);
+    sort(&mut input.to_vec(), &mut recorder);
+    recorder.into_replay()
+}
+
+/// a recorded sort that can rebuild the array as it was after any step
+#[derive(Debug, Clone)]
+pub struct Replay<T> {
+    initial: Vec<T>,
+    steps: Vec<SortStep<T>>,
+}
+
+impl<T: Clone> Replay<T> {
+    /// how many steps the sort took
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn steps(&self) -> &[SortStep<T>] {
+        &self.steps
+    }
+
+    /// the array after the first `step` steps, `state_at(0)` is the input
+    /// and `state_at(len())` the sorted output
+    pub fn state_at(&self, step: usize) -> Vec<T> {
+        assert!(step <= self.len(), "only {} steps recorded", self.len());
+
+        let mut state = self.initial.clone();
+        for taken in &self.steps[..step] {
+            apply(&mut state, taken);
+        }
+        state
+    }
+
+    /// every state from the input to the sorted output, `len() + 1` of them
+    pub fn states(&self) -> impl Iterator<Item = Vec<T>> + '_ {
+        let mut state = self.initial.clone();
+        let later = self.steps.iter().map(move |taken| {
+            apply(&mut state, taken);
+            state.clone()
+        });
+
+        std::iter::once(self.initial.clone()).chain(later)
+    }
+}
+
+impl<T: Clone + fmt::Debug + Into<i64>> Replay<T> {
+    /// the array after `step` steps drawn as bars `height` rows tall, under a
+    /// caption naming the step and over a row marking the indices it touched
+    pub fn render(&self, step: usize, height: usize) -> String {
+        self.render_state(step, &self.state_at(step), height)
+    }
+
+    /// writes every step to `out`, e.g. `io::stdout()` for watching a sort
+    /// in the terminal
+    ///
+    /// walks the states once rather than replaying from the start each step
+    pub fn play(&self, out: &mut impl Write, height: usize) -> io::Result<()> {
+        for (step, state) in self.states().enumerate() {
+            writeln!(out, "{}", self.render_state(step, &state, height))?;
+        }
+        Ok(())
+    }
+
+    // `state` has to be what `state_at(step)` would give
+    fn render_state(&self, step: usize, state: &[T], height: usize) -> String {
+        let (caption, marks) = match step.checked_sub(1).map(|prev| &self.steps[prev]) {
+            Some(taken) => (
+                format!("step {step}/{}: {taken}", self.len()),
+                taken.indices(),
+            ),
+            None => (format!("step 0/{}: start", self.len()), Vec::new()),
+        };
+
+        format!("{caption}\n{}", render_bars(state, &marks, height))
+    }
+}
+
+fn apply<T: Clone>(state: &mut [T], step: &SortStep<T>) {
+    match step {
+        SortStep::Probe(_) | SortStep::Compare(_, _) => {}
+        SortStep::Swap(a, b) => state.swap(*a, *b),
+        SortStep::Write(idx, value) => state[*idx] = value.clone(),
+    }
+}
+
+/// draws `values` as columns of `#`, the smallest one row tall and the
+/// largest `height` rows, with a `^` under each index in `marks`
+pub fn render_bars<T: Clone + Into<i64>>(values: &[T], marks: &[usize], height: usize) -> String {
+    assert!(height > 0, "bars need at least one row");
+
+    let values: Vec<i64> = values.iter().map(|value| value.clone().into()).collect();
+    let min = values.iter().copied().min().unwrap_or(0);
+    let max = values.iter().copied().max().unwrap_or(0);
+
+    // widened so a span over the whole of `i64` doesn't overflow
+    let bar_height = |value: i64| {
+        if max == min {
+            return height;
+        }
+        let scaled =
+            (value as i128 - min as i128) * (height as i128 - 1) / (max as i128 - min as i128);
+        scaled as usize + 1
+    };
+    let bars: Vec<usize> = values.iter().map(|&value| bar_height(value)).collect();
+
+    let mut out = String::new();
+    for row in (1..=height).rev() {
+        let line: String = bars
+            .iter()
+            .map(|&bar| if bar >= row { '#' } else { ' ' })
+            .collect();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+
+    let marked: String = (0..values.len())
+        .map(|idx| if marks.contains(&idx) { '^' } else { ' ' })
+        .collect();
+    out.push_str(marked.trim_end());
+    out.push('\n');
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bubble_sort::{bubble_sort_by_traced, bubble_sort_early_exit_by_traced};
+    use crate::distribution_sort::{
+        bucket_sort_traced, counting_sort_traced, msd_radix_sort_traced, radix_sort_i32_traced,
+    };
+    use crate::merge_sort::{MergeVariant, merge_sort_by_traced};
+    use crate::rng::Rng;
+    use crate::sort_check::is_sorted;
+    use crate::sorting::all_sorters;
+
+    type Sort = Box<dyn Fn(&mut [i32], &mut SortRecorder<i32>)>;
+
+    /// every sorter in the crate, then the traced sorts that aren't one
+    fn traced_sorts() -> Vec<(String, Sort)> {
+        let mut sorts: Vec<(String, Sort)> = all_sorters()
+            .into_iter()
+            .map(|sorter| {
+                let name = sorter.name().to_string();
+                let sort: Sort =
+                    Box::new(move |arr, tracer| sorter.sort_by_traced(arr, &mut i32::cmp, tracer));
+                (name, sort)
+            })
+            .collect();
+
+        let others: [(&str, Sort); 3] = [
+            (
+                "bubble (early exit)",
+                Box::new(|arr, tracer| bubble_sort_early_exit_by_traced(arr, i32::cmp, tracer)),
+            ),
+            ("counting", Box::new(counting_sort_traced)),
+            ("radix", Box::new(radix_sort_i32_traced)),
+        ];
+        sorts.extend(others.map(|(name, sort)| (name.to_string(), sort)));
+        sorts
+    }
+
+    #[test]
+    fn test_replay_ends_where_the_sort_did() {
+        let mut rng = Rng::new(48);
+
+        for len in [0, 1, 2, 5, 17, 100] {
+            let input = rng.vec_i32(len, -10..10);
+
+            for (name, sort) in traced_sorts() {
+                let mut sorted = input.clone();
+                let mut recorder = SortRecorder::new(&input);
+                sort(&mut sorted, &mut recorder);
+
+                let replay = record(&input, sort);
+                assert_eq!(recorder.steps(), replay.steps(), "{name}");
+                if len == 0 {
+                    assert!(replay.is_empty(), "{name}");
+                }
+                assert_eq!(replay.state_at(0), input, "{name}");
+                assert_eq!(replay.state_at(replay.len()), sorted, "{name}");
+                assert!(is_sorted(&sorted), "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_long_sorts_replay() {
+        // long enough for tim sort to gallop and intro sort to fall back to heap sort
+        let mut rng = Rng::new(482);
+        let mut input: Vec<i32> = (0..2000).collect();
+        for chunk in input.chunks_mut(300).skip(1).step_by(2) {
+            chunk.reverse();
+        }
+        input.extend(rng.vec_i32(500, 0..2000));
+
+        for (name, sort) in traced_sorts() {
+            let replay = record(&input, sort);
+            let sorted = replay.state_at(replay.len());
+            assert!(is_sorted(&sorted), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_non_integer_sorts_replay() {
+        let mut rng = Rng::new(483);
+
+        let words: Vec<String> = (0..200).map(|_| format!("{:x}", rng.below(5000))).collect();
+        let replay = record(&words, msd_radix_sort_traced);
+        assert!(is_sorted(&replay.state_at(replay.len())));
+
+        let floats: Vec<f64> = (0..200).map(|_| rng.next_f64()).collect();
+        let replay = record(&floats, bucket_sort_traced);
+        let sorted = replay.state_at(replay.len());
+        assert!(sorted.is_sorted_by(|a, b| a <= b));
+    }
+
+    #[test]
+    fn test_states_walk_every_step() {
+        let input = Rng::new(480).vec_i32(30, 0..50);
+
+        for (name, sort) in traced_sorts() {
+            let replay = record(&input, sort);
+            let states: Vec<Vec<i32>> = replay.states().collect();
+            assert_eq!(states.len(), replay.len() + 1, "{name}");
+
+            for (step, state) in states.iter().enumerate().step_by(7) {
+                assert_eq!(*state, replay.state_at(step), "{name} at {step}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_bubble_sort_steps() {
+        let replay = record(&[3, 1, 2], |arr, tracer| {
+            bubble_sort_by_traced(arr, i32::cmp, tracer)
+        });
+
+        assert_eq!(
+            replay.steps(),
+            [
+                SortStep::Compare(0, 1),
+                SortStep::Swap(0, 1),
+                SortStep::Compare(1, 2),
+                SortStep::Swap(1, 2),
+                SortStep::Compare(0, 1),
+            ]
+        );
+        assert_eq!(replay.state_at(2), [1, 3, 2]);
+        assert_eq!(replay.state_at(5), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_counting_sort_writes_are_replayed() {
+        let replay = record(&[2, 0, 2, 1], counting_sort_traced);
+
+        let writes: Vec<_> = replay
+            .steps()
+            .iter()
+            .filter(|step| matches!(step, SortStep::Write(_, _)))
+            .cloned()
+            .collect();
+        assert_eq!(
+            writes,
+            [
+                SortStep::Write(0, 0),
+                SortStep::Write(1, 1),
+                SortStep::Write(2, 2),
+                SortStep::Write(3, 2),
+            ]
+        );
+        // halfway through writing back, the old values are still in the tail
+        assert_eq!(replay.state_at(6), [0, 1, 2, 1]);
+    }
+
+    #[test]
+    fn test_render() {
+        let replay = record(&[3, 1, 2], |arr, tracer| {
+            bubble_sort_by_traced(arr, i32::cmp, tracer)
+        });
+
+        assert_eq!(replay.render(0, 3), "step 0/5: start\n#\n# #\n###\n\n");
+        assert_eq!(
+            replay.render(2, 3),
+            "step 2/5: swap 0 and 1\n #\n ##\n###\n^^\n"
+        );
+        assert_eq!(
+            replay.render(3, 3),
+            "step 3/5: compare 1 and 2\n #\n ##\n###\n ^^\n"
+        );
+    }
+
+    #[test]
+    fn test_play_renders_the_same_frames() {
+        let replay = record(&Rng::new(481).vec_i32(12, 0..20), |arr, tracer| {
+            bubble_sort_by_traced(arr, i32::cmp, tracer)
+        });
+
+        let mut played = Vec::new();
+        replay.play(&mut played, 4).unwrap();
+
+        let expected: String = (0..=replay.len())
+            .map(|step| replay.render(step, 4) + "\n")
+            .collect();
+        assert_eq!(String::from_utf8(played).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_merge_sort_writes_are_replayed() {
+        let replay = record(&[4, 1, 3, 2], |arr, tracer| {
+            merge_sort_by_traced(arr, MergeVariant::TopDown, i32::cmp, tracer)
+        });
+
+        // both halves and then the whole are merged by writes alone
+        assert!(
+            replay
+                .steps()
+                .iter()
+                .all(|step| !matches!(step, SortStep::Swap(_, _)))
+        );
+        assert!(replay.states().any(|state| state == [1, 4, 2, 3]));
+        assert_eq!(replay.state_at(replay.len()), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_render_bars_scaling() {
+        // equal values fill every row
+        assert_eq!(render_bars(&[7, 7], &[], 2), "##\n##\n\n");
+        assert_eq!(render_bars::<i32>(&[], &[], 2), "\n\n\n");
+
+        // the extremes of i64 don't overflow the scaling
+        assert_eq!(
+            render_bars(&[i64::MIN, 0, i64::MAX], &[2], 3),
+            "  #\n ##\n###\n  ^\n"
+        );
+    }
+}
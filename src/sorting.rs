@@ -0,0 +1,378 @@
+use std::cmp::Ordering;
+
+use crate::bubble_sort::bubble_sort_by_traced;
+use crate::elementary_sort::{
+    GapSequence, binary_insertion_sort_by_traced, cocktail_shaker_sort_by_traced,
+    gnome_sort_by_traced, insertion_sort_by_traced, selection_sort_by_traced, shell_sort_by_traced,
+};
+use crate::heap_sort::heap_sort_by_traced;
+use crate::merge_sort::{MergeVariant, merge_sort_by_traced};
+use crate::quicksort::{intro_sort_by_traced, quick_sort_by_traced};
+use crate::tim_sort::tim_sort_by_traced;
+use crate::tracer::{NoopTracer, TraceEvent, Tracer, WriteTracer};
+
+/// common interface over every sorting algorithm in the crate
+///
+/// generic over the element type so an algorithm can put its own bounds on it,
+/// and usable as `dyn Sorter<T>` to run several algorithms side by side
+pub trait Sorter<T> {
+    fn name(&self) -> &'static str;
+
+    /// whether elements that compare equal keep their original order
+    fn is_stable(&self) -> bool;
+
+    /// the one method each algorithm implements, the others build on it
+    ///
+    /// reports every step to `tracer`, so any sorter can be recorded with a
+    /// `SortRecorder` and replayed
+    fn sort_by_traced(
+        &self,
+        arr: &mut [T],
+        compare: &mut dyn FnMut(&T, &T) -> Ordering,
+        tracer: &mut dyn WriteTracer<T>,
+    );
+
+    fn sort_by_dyn(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        self.sort_by_traced(arr, compare, &mut NoopTracer);
+    }
+
+    fn sort(&self, arr: &mut [T])
+    where
+        T: Ord,
+    {
+        self.sort_by_dyn(arr, &mut T::cmp);
+    }
+
+    fn sort_by<F>(&self, arr: &mut [T], mut compare: F)
+    where
+        Self: Sized,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.sort_by_dyn(arr, &mut compare);
+    }
+
+    fn sort_by_key<K, F>(&self, arr: &mut [T], mut key: F)
+    where
+        Self: Sized,
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by_dyn(arr, &mut |a, b| key(a).cmp(&key(b)));
+    }
+}
+
+// the `_traced` sorts take an `impl WriteTracer<T>`, which has to be sized
+struct DynTracer<'a, T>(&'a mut dyn WriteTracer<T>);
+
+impl<T> Tracer for DynTracer<'_, T> {
+    fn trace(&mut self, event: TraceEvent) {
+        self.0.trace(event);
+    }
+}
+
+impl<T> WriteTracer<T> for DynTracer<'_, T> {
+    fn write(&mut self, idx: usize, value: &T) {
+        self.0.write(idx, value);
+    }
+}
+
+// a unit struct sorter over any element type, backed by a `_by_traced` function
+macro_rules! unit_sorter {
+    ($($sorter:ident => $name:literal, stable: $stable:literal, $sort_by_traced:path;)*) => {
+        $(
+            #[derive(Debug, Default, Clone, Copy)]
+            pub struct $sorter;
+
+            impl<T> Sorter<T> for $sorter {
+                fn name(&self) -> &'static str {
+                    $name
+                }
+
+                fn is_stable(&self) -> bool {
+                    $stable
+                }
+
+                fn sort_by_traced(
+                    &self,
+                    arr: &mut [T],
+                    compare: &mut dyn FnMut(&T, &T) -> Ordering,
+                    tracer: &mut dyn WriteTracer<T>,
+                ) {
+                    $sort_by_traced(arr, compare, &mut DynTracer(tracer));
+                }
+            }
+        )*
+    };
+}
+
+unit_sorter! {
+    BubbleSort => "bubble", stable: true, bubble_sort_by_traced;
+    QuickSort => "quick", stable: false, quick_sort_by_traced;
+    IntroSort => "intro", stable: false, intro_sort_by_traced;
+    HeapSort => "heap", stable: false, heap_sort_by_traced;
+    InsertionSort => "insertion", stable: true, insertion_sort_by_traced;
+    BinaryInsertionSort => "binary insertion", stable: true, binary_insertion_sort_by_traced;
+    SelectionSort => "selection", stable: false, selection_sort_by_traced;
+    CocktailShakerSort => "cocktail shaker", stable: true, cocktail_shaker_sort_by_traced;
+    GnomeSort => "gnome", stable: true, gnome_sort_by_traced;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ShellSort(pub GapSequence);
+
+impl<T> Sorter<T> for ShellSort {
+    fn name(&self) -> &'static str {
+        match self.0 {
+            GapSequence::Shell => "shell (shell gaps)",
+            GapSequence::Knuth => "shell (knuth gaps)",
+            GapSequence::Ciura => "shell (ciura gaps)",
+        }
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn sort_by_traced(
+        &self,
+        arr: &mut [T],
+        compare: &mut dyn FnMut(&T, &T) -> Ordering,
+        tracer: &mut dyn WriteTracer<T>,
+    ) {
+        shell_sort_by_traced(arr, self.0, compare, &mut DynTracer(tracer));
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MergeSort(pub MergeVariant);
+
+impl<T: Clone> Sorter<T> for MergeSort {
+    fn name(&self) -> &'static str {
+        match self.0 {
+            MergeVariant::TopDown => "merge (top down)",
+            MergeVariant::BottomUp => "merge (bottom up)",
+            MergeVariant::Natural => "merge (natural)",
+        }
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn sort_by_traced(
+        &self,
+        arr: &mut [T],
+        compare: &mut dyn FnMut(&T, &T) -> Ordering,
+        tracer: &mut dyn WriteTracer<T>,
+    ) {
+        merge_sort_by_traced(arr, self.0, compare, &mut DynTracer(tracer));
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TimSort;
+
+impl<T: Clone> Sorter<T> for TimSort {
+    fn name(&self) -> &'static str {
+        "tim"
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn sort_by_traced(
+        &self,
+        arr: &mut [T],
+        compare: &mut dyn FnMut(&T, &T) -> Ordering,
+        tracer: &mut dyn WriteTracer<T>,
+    ) {
+        tim_sort_by_traced(arr, compare, &mut DynTracer(tracer));
+    }
+}
+
+/// every sorter in the crate
+pub fn all_sorters<T: Clone>() -> Vec<Box<dyn Sorter<T>>> {
+    vec![
+        Box::new(BubbleSort),
+        Box::new(QuickSort),
+        Box::new(IntroSort),
+        Box::new(HeapSort),
+        Box::new(InsertionSort),
+        Box::new(BinaryInsertionSort),
+        Box::new(SelectionSort),
+        Box::new(ShellSort(GapSequence::Shell)),
+        Box::new(ShellSort(GapSequence::Knuth)),
+        Box::new(ShellSort(GapSequence::Ciura)),
+        Box::new(CocktailShakerSort),
+        Box::new(GnomeSort),
+        Box::new(MergeSort(MergeVariant::TopDown)),
+        Box::new(MergeSort(MergeVariant::BottomUp)),
+        Box::new(MergeSort(MergeVariant::Natural)),
+        Box::new(TimSort),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::sort_check::{is_permutation, is_sorted_by, is_stable_by};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Person {
+        name: String,
+        age: u32,
+    }
+
+    fn people(rng: &mut Rng, len: usize) -> Vec<Person> {
+        (0..len)
+            .map(|i| Person {
+                name: format!("person-{}", rng.below(1000)),
+                age: i as u32 % 7,
+            })
+            .collect()
+    }
+
+    fn check_sorter<S: Sorter<Person> + Sorter<i32> + Sorter<String>>(sorter: S) {
+        let mut rng = Rng::new(38);
+        let name = Sorter::<i32>::name(&sorter);
+
+        for len in 0..80 {
+            let mut ints = rng.vec_i32(len, -50..50);
+            let mut expected = ints.clone();
+            expected.sort();
+            sorter.sort(&mut ints);
+            assert_eq!(ints, expected, "{name} on {len} ints");
+
+            let mut words: Vec<String> = (0..len).map(|_| rng.below(100).to_string()).collect();
+            let mut expected = words.clone();
+            expected.sort_by(|a, b| b.cmp(a));
+            sorter.sort_by(&mut words, |a, b| b.cmp(a));
+            assert_eq!(words, expected, "{name} descending on {len} strings");
+
+            let mut group = people(&mut rng, len);
+            let mut expected = group.clone();
+            expected.sort_by_key(|p| p.age);
+            sorter.sort_by_key(&mut group, |p| p.age);
+
+            // not every sorter is stable, so only the keys have to line up
+            let ages: Vec<u32> = group.iter().map(|p| p.age).collect();
+            let expected_ages: Vec<u32> = expected.iter().map(|p| p.age).collect();
+            assert_eq!(ages, expected_ages, "{name} by key on {len} structs");
+        }
+    }
+
+    /// sorts records with few distinct keys and checks that records with equal
+    /// keys come out in the order they went in
+    fn check_stable(sorter: &dyn Sorter<(u8, usize)>) {
+        let mut rng = Rng::new(42);
+
+        for len in [0, 1, 2, 3, 10, 50, 200] {
+            // (key, original position)
+            let input: Vec<(u8, usize)> = (0..len).map(|i| (rng.below(5) as u8, i)).collect();
+            let mut by_key = |a: &(u8, usize), b: &(u8, usize)| a.0.cmp(&b.0);
+
+            let mut records = input.clone();
+            sorter.sort_by_dyn(&mut records, &mut by_key);
+
+            assert!(
+                is_sorted_by(&records, by_key),
+                "{} didn't sort",
+                sorter.name()
+            );
+            assert!(
+                is_stable_by(&input, &records, by_key),
+                "{} reordered equal keys in {records:?}",
+                sorter.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_stable_sorters_keep_equal_keys_in_order() {
+        let stable: Vec<_> = all_sorters()
+            .into_iter()
+            .filter(|sorter| sorter.is_stable())
+            .collect();
+        assert!(stable.len() >= 9);
+
+        for sorter in stable {
+            check_stable(sorter.as_ref());
+        }
+    }
+
+    #[test]
+    fn test_bubble_sort() {
+        check_sorter(BubbleSort);
+    }
+
+    #[test]
+    fn test_quick_sort() {
+        check_sorter(QuickSort);
+    }
+
+    #[test]
+    fn test_intro_sort() {
+        check_sorter(IntroSort);
+    }
+
+    #[test]
+    fn test_heap_sort() {
+        check_sorter(HeapSort);
+    }
+
+    #[test]
+    fn test_elementary_sorts() {
+        check_sorter(InsertionSort);
+        check_sorter(BinaryInsertionSort);
+        check_sorter(SelectionSort);
+        check_sorter(ShellSort(GapSequence::Shell));
+        check_sorter(ShellSort(GapSequence::Knuth));
+        check_sorter(ShellSort(GapSequence::Ciura));
+        check_sorter(CocktailShakerSort);
+        check_sorter(GnomeSort);
+    }
+
+    #[test]
+    fn test_merge_sort() {
+        check_sorter(MergeSort(MergeVariant::TopDown));
+        check_sorter(MergeSort(MergeVariant::BottomUp));
+        check_sorter(MergeSort(MergeVariant::Natural));
+    }
+
+    #[test]
+    fn test_tim_sort() {
+        check_sorter(TimSort);
+    }
+
+    #[test]
+    fn test_all_sorters_through_dyn() {
+        let mut rng = Rng::new(380);
+        let input = rng.vec_i32(200, i32::MIN..i32::MAX);
+        let mut expected = input.clone();
+        expected.sort();
+
+        for sorter in all_sorters::<i32>() {
+            let mut arr = input.clone();
+            sorter.sort(&mut arr);
+            assert_eq!(arr, expected, "{}", sorter.name());
+
+            let mut arr = input.clone();
+            sorter.sort_by_dyn(&mut arr, &mut |a, b| b.cmp(a));
+            assert!(is_sorted_by(&arr, |a, b| b.cmp(a)), "{}", sorter.name());
+            assert!(is_permutation(&input, &arr), "{}", sorter.name());
+        }
+    }
+
+    #[test]
+    fn test_sort_floats_by_partial_cmp() {
+        let mut arr = [3.5, -1.0, 2.25, 0.0, -7.5];
+
+        for sorter in all_sorters::<f64>() {
+            sorter.sort_by_dyn(&mut arr, &mut |a: &f64, b: &f64| a.total_cmp(b));
+            assert_eq!(arr, [-7.5, -1.0, 0.0, 2.25, 3.5], "{}", sorter.name());
+            arr.reverse();
+        }
+    }
+}
@@ -0,0 +1,716 @@
+use std::cmp::Ordering;
+use std::ops::Range;
+
+use crate::elementary_sort::binary_insert_tail;
+use crate::tracer::{NoopTracer, TraceEvent, Tracer, WriteTracer};
+
+// inputs shorter than this skip the run machinery and get a binary insertion sort
+const MIN_MERGE: usize = 64;
+
+// how many wins in a row a run needs before a merge starts galloping
+const MIN_GALLOP: usize = 7;
+
+pub fn tim_sort<T: Ord + Clone>(arr: &mut [T]) {
+    tim_sort_by(arr, T::cmp);
+}
+
+/// adaptive, stable merge sort in the style of Python's and Java's timsort
+///
+/// splits the input into natural runs, extends short ones to `min_run` with
+/// binary insertion sort, and merges them off a stack that keeps run lengths
+/// growing like the Fibonacci numbers. merges gallop when one side keeps
+/// winning, so presorted or blocky data costs close to O(n)
+pub fn tim_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    tim_sort_by_traced(arr, compare, &mut NoopTracer);
+}
+
+/// timsort reporting its comparisons, the swaps that reverse descending runs
+/// and build the short ones up, and every value a merge writes back
+///
+/// like `merge_sort_by_traced`, compares against the copy of a run in the
+/// scratch buffer name the index that element started out at
+pub fn tim_sort_by_traced<T, F>(arr: &mut [T], mut compare: F, tracer: &mut impl WriteTracer<T>)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+
+    if len < MIN_MERGE {
+        let run = count_run(arr, &mut compare, tracer);
+        binary_insert_tail(arr, run, &mut compare, tracer);
+        return;
+    }
+
+    let min_run = min_run_length(len);
+    let mut sorter = TimSorter {
+        compare: &mut compare,
+        tracer,
+        scratch: Vec::new(),
+        min_gallop: MIN_GALLOP,
+        runs: Vec::new(),
+    };
+
+    let mut start = 0;
+    while start < len {
+        let tracer = &mut *sorter.tracer;
+        let mut shifted = |event: TraceEvent| tracer.trace(event.shifted(start));
+        let mut run = count_run(&mut arr[start..], sorter.compare, &mut shifted);
+
+        if run < min_run {
+            let forced = min_run.min(len - start);
+            binary_insert_tail(
+                &mut arr[start..start + forced],
+                run,
+                sorter.compare,
+                &mut shifted,
+            );
+            run = forced;
+        }
+
+        sorter.runs.push(Run { start, len: run });
+        sorter.merge_collapse(arr);
+        start += run;
+    }
+
+    sorter.merge_force_collapse(arr);
+}
+
+/// run length the input is cut into: `len` itself when it is small, otherwise
+/// a number in `32..=64` such that `len / min_run` is a power of two or just
+/// below one, which keeps the final merges balanced
+pub fn min_run_length(mut len: usize) -> usize {
+    // set if any bit shifted off was set
+    let mut remainder = 0;
+
+    while len >= MIN_MERGE {
+        remainder |= len & 1;
+        len >>= 1;
+    }
+
+    len + remainder
+}
+
+/// length of the run at the front of `arr`, a strictly descending run is
+/// reversed in place so every run comes out ascending
+fn count_run<T, F>(arr: &mut [T], compare: &mut F, tracer: &mut impl Tracer) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if arr.len() < 2 {
+        return arr.len();
+    }
+
+    tracer.trace(TraceEvent::Compare(1, 0));
+    let descending = compare(&arr[1], &arr[0]) == Ordering::Less;
+    let mut end = 2;
+
+    while end < arr.len() {
+        tracer.trace(TraceEvent::Compare(end, end - 1));
+        if (compare(&arr[end], &arr[end - 1]) == Ordering::Less) != descending {
+            break;
+        }
+        end += 1;
+    }
+
+    if descending {
+        for i in 0..end / 2 {
+            tracer.trace(TraceEvent::Swap(i, end - 1 - i));
+        }
+        arr[..end].reverse();
+    }
+
+    end
+}
+
+/// first index in `0..len` for which `pred` fails, for a `pred` that holds
+/// for a prefix of the indices, found by galloping out from the front and
+/// then binary searching the last step
+///
+/// O(log k) where k is the answer, cheaper than a plain binary search when the
+/// answer is near the front
+fn gallop_from_front<P>(len: usize, mut pred: P) -> usize
+where
+    P: FnMut(usize) -> bool,
+{
+    let mut bound = 1;
+    while bound <= len && pred(bound - 1) {
+        bound *= 2;
+    }
+
+    // pred held below low, and failed at high unless high is the end
+    let low = bound / 2;
+    let high = if bound <= len { bound - 1 } else { len };
+
+    bisect(low, high, pred)
+}
+
+/// same as `gallop_from_front`, galloping in from the back
+fn gallop_from_back<P>(len: usize, mut pred: P) -> usize
+where
+    P: FnMut(usize) -> bool,
+{
+    let mut bound = 1;
+    while bound <= len && !pred(len - bound) {
+        bound *= 2;
+    }
+
+    // pred failed from high on, and held at low - 1 unless low is the start
+    let high = len - bound / 2;
+    let low = if bound <= len { len - bound + 1 } else { 0 };
+
+    bisect(low, high, pred)
+}
+
+// first index in `low..high` for which `pred` fails, or `high` if it never does
+fn bisect<P>(mut low: usize, mut high: usize, mut pred: P) -> usize
+where
+    P: FnMut(usize) -> bool,
+{
+    while low < high {
+        let middle = low + (high - low) / 2;
+
+        if pred(middle) {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+
+    low
+}
+
+/// reports a block copied into `arr[range]` as one write per element
+fn trace_writes<T>(tracer: &mut impl WriteTracer<T>, arr: &[T], range: Range<usize>) {
+    let start = range.start;
+    for (idx, value) in arr[range].iter().enumerate() {
+        tracer.write(start + idx, value);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    start: usize,
+    len: usize,
+}
+
+struct TimSorter<'a, T, F, R> {
+    compare: &'a mut F,
+    tracer: &'a mut R,
+    scratch: Vec<T>,
+    // adapts to the data: drops while galloping pays off, rises when it doesn't
+    min_gallop: usize,
+    // pending runs, left to right
+    runs: Vec<Run>,
+}
+
+impl<T, F, R> TimSorter<'_, T, F, R>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+    R: WriteTracer<T>,
+{
+    /// merges until, for the runs X, Y, Z on top of the stack,
+    /// `X > Y + Z` and `Y > Z` hold all the way down
+    ///
+    /// also checks the run below X, the fix for the case the original
+    /// timsort missed, where the invariant broke deeper in the stack
+    fn merge_collapse(&mut self, arr: &mut [T]) {
+        while self.runs.len() > 1 {
+            let mut n = self.runs.len() - 2;
+            let len = |i: usize| self.runs[i].len;
+
+            if (n > 0 && len(n - 1) <= len(n) + len(n + 1))
+                || (n > 1 && len(n - 2) <= len(n - 1) + len(n))
+            {
+                // merge Y with the smaller of its neighbours
+                if len(n - 1) < len(n + 1) {
+                    n -= 1;
+                }
+            } else if len(n) > len(n + 1) {
+                break;
+            }
+
+            self.merge_at(arr, n);
+        }
+
+        debug_assert!(self.invariants_hold());
+    }
+
+    fn merge_force_collapse(&mut self, arr: &mut [T]) {
+        while self.runs.len() > 1 {
+            let mut n = self.runs.len() - 2;
+
+            if n > 0 && self.runs[n - 1].len < self.runs[n + 1].len {
+                n -= 1;
+            }
+
+            self.merge_at(arr, n);
+        }
+    }
+
+    fn invariants_hold(&self) -> bool {
+        self.runs.windows(2).all(|w| w[0].len > w[1].len)
+            && self.runs.windows(3).all(|w| w[0].len > w[1].len + w[2].len)
+    }
+
+    /// merges the runs at `n` and `n + 1` of the stack
+    fn merge_at(&mut self, arr: &mut [T], n: usize) {
+        let Run { start, len: len_a } = self.runs[n];
+        let len_b = self.runs[n + 1].len;
+
+        self.runs[n].len = len_a + len_b;
+        self.runs.remove(n + 1);
+
+        // A is arr[start..mid] and B is arr[mid..mid + len_b]
+        let mid = start + len_a;
+        let compare = &mut *self.compare;
+        let tracer = &mut *self.tracer;
+
+        // the front of A that is no bigger than B's first element is already in place
+        let skip = gallop_from_front(len_a, |i| {
+            tracer.trace(TraceEvent::Compare(start + i, mid));
+            compare(&arr[start + i], &arr[mid]) != Ordering::Greater
+        });
+        if skip == len_a {
+            return;
+        }
+
+        // and so is the back of B that is no smaller than A's last element
+        let keep = gallop_from_back(len_b, |j| {
+            tracer.trace(TraceEvent::Compare(mid + j, mid - 1));
+            compare(&arr[mid + j], &arr[mid - 1]) == Ordering::Less
+        });
+
+        let lo = start + skip;
+        let hi = mid + keep;
+
+        if mid - lo <= keep {
+            self.merge_lo(arr, lo, mid, hi);
+        } else {
+            self.merge_hi(arr, lo, mid, hi);
+        }
+    }
+
+    /// merges `arr[lo..mid]` into `arr[mid..hi]` front to back with the left
+    /// run, the shorter one, copied out
+    fn merge_lo(&mut self, arr: &mut [T], lo: usize, mid: usize, hi: usize) {
+        let compare = &mut *self.compare;
+        let tracer = &mut *self.tracer;
+        let scratch = &mut self.scratch;
+        scratch.clear();
+        scratch.extend_from_slice(&arr[lo..mid]);
+
+        // scratch[i] started out at lo + i
+        let (mut i, mut j, mut k) = (0, mid, lo);
+        let mut min_gallop = self.min_gallop;
+
+        'merge: while i < scratch.len() && j < hi {
+            let mut wins_a = 0;
+            let mut wins_b = 0;
+
+            // one element at a time until one side keeps winning
+            while wins_a < min_gallop && wins_b < min_gallop {
+                tracer.trace(TraceEvent::Compare(j, lo + i));
+                if compare(&arr[j], &scratch[i]) == Ordering::Less {
+                    arr[k] = arr[j].clone();
+                    j += 1;
+                    wins_a = 0;
+                    wins_b += 1;
+                } else {
+                    arr[k] = scratch[i].clone();
+                    i += 1;
+                    wins_a += 1;
+                    wins_b = 0;
+                }
+                tracer.write(k, &arr[k]);
+                k += 1;
+
+                if i == scratch.len() || j == hi {
+                    break 'merge;
+                }
+            }
+
+            // then gallop, copying whole blocks, for as long as that pays off
+            loop {
+                let count_a = gallop_from_front(scratch.len() - i, |a| {
+                    tracer.trace(TraceEvent::Compare(lo + i + a, j));
+                    compare(&scratch[i + a], &arr[j]) != Ordering::Greater
+                });
+                arr[k..k + count_a].clone_from_slice(&scratch[i..i + count_a]);
+                trace_writes(tracer, arr, k..k + count_a);
+                i += count_a;
+                k += count_a;
+                if i == scratch.len() {
+                    break 'merge;
+                }
+
+                let count_b = gallop_from_front(hi - j, |b| {
+                    tracer.trace(TraceEvent::Compare(j + b, lo + i));
+                    compare(&arr[j + b], &scratch[i]) == Ordering::Less
+                });
+                for _ in 0..count_b {
+                    arr[k] = arr[j].clone();
+                    tracer.write(k, &arr[k]);
+                    j += 1;
+                    k += 1;
+                }
+                if j == hi {
+                    break 'merge;
+                }
+
+                min_gallop = min_gallop.saturating_sub(1);
+                if count_a < MIN_GALLOP && count_b < MIN_GALLOP {
+                    break;
+                }
+            }
+
+            // leaving gallop mode costs a penalty
+            min_gallop += 2;
+        }
+
+        self.min_gallop = min_gallop.max(1);
+
+        // whatever is left of B is already in place
+        let rest = scratch.len() - i;
+        arr[k..k + rest].clone_from_slice(&scratch[i..]);
+        trace_writes(tracer, arr, k..k + rest);
+    }
+
+    /// merges `arr[lo..mid]` and `arr[mid..hi]` back to front with the right
+    /// run, the shorter one, copied out
+    fn merge_hi(&mut self, arr: &mut [T], lo: usize, mid: usize, hi: usize) {
+        let compare = &mut *self.compare;
+        let tracer = &mut *self.tracer;
+        let scratch = &mut self.scratch;
+        scratch.clear();
+        scratch.extend_from_slice(&arr[mid..hi]);
+
+        // `i` and `j` are where what is left of A and B ends, `k` where the
+        // next largest goes, scratch[j] started out at mid + j
+        let (mut i, mut j, mut k) = (mid, scratch.len(), hi);
+        let mut min_gallop = self.min_gallop;
+
+        'merge: while i > lo && j > 0 {
+            let mut wins_a = 0;
+            let mut wins_b = 0;
+
+            while wins_a < min_gallop && wins_b < min_gallop {
+                k -= 1;
+                tracer.trace(TraceEvent::Compare(mid + j - 1, i - 1));
+                if compare(&scratch[j - 1], &arr[i - 1]) == Ordering::Less {
+                    arr[k] = arr[i - 1].clone();
+                    i -= 1;
+                    wins_a += 1;
+                    wins_b = 0;
+                } else {
+                    arr[k] = scratch[j - 1].clone();
+                    j -= 1;
+                    wins_a = 0;
+                    wins_b += 1;
+                }
+                tracer.write(k, &arr[k]);
+
+                if i == lo || j == 0 {
+                    break 'merge;
+                }
+            }
+
+            loop {
+                // the tail of A that is bigger than B's last element
+                let first = gallop_from_back(i - lo, |a| {
+                    tracer.trace(TraceEvent::Compare(lo + a, mid + j - 1));
+                    compare(&arr[lo + a], &scratch[j - 1]) != Ordering::Greater
+                });
+                let count_a = i - lo - first;
+                for _ in 0..count_a {
+                    k -= 1;
+                    i -= 1;
+                    arr[k] = arr[i].clone();
+                    tracer.write(k, &arr[k]);
+                }
+                if i == lo {
+                    break 'merge;
+                }
+
+                // the tail of B that is no smaller than A's last element
+                let first = gallop_from_back(j, |b| {
+                    tracer.trace(TraceEvent::Compare(mid + b, i - 1));
+                    compare(&scratch[b], &arr[i - 1]) == Ordering::Less
+                });
+                let count_b = j - first;
+                arr[k - count_b..k].clone_from_slice(&scratch[first..j]);
+                trace_writes(tracer, arr, k - count_b..k);
+                j -= count_b;
+                k -= count_b;
+                if j == 0 {
+                    break 'merge;
+                }
+
+                min_gallop = min_gallop.saturating_sub(1);
+                if count_a < MIN_GALLOP && count_b < MIN_GALLOP {
+                    break;
+                }
+            }
+
+            min_gallop += 2;
+        }
+
+        self.min_gallop = min_gallop.max(1);
+
+        // whatever is left of A is already in place
+        arr[lo..lo + j].clone_from_slice(&scratch[..j]);
+        trace_writes(tracer, arr, lo..lo + j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_search_algo::partition_point;
+    use crate::rng::Rng;
+    use crate::sort_check::is_sorted;
+    use crate::tracer::CountingTracer;
+
+    fn count_compares(arr: &mut [i32]) -> usize {
+        let mut compares = 0;
+        tim_sort_by(arr, |a, b| {
+            compares += 1;
+            a.cmp(b)
+        });
+        compares
+    }
+
+    // sorted data with `swaps` random pairs exchanged
+    fn nearly_sorted(rng: &mut Rng, len: usize, swaps: usize) -> Vec<i32> {
+        let mut arr: Vec<i32> = (0..len as i32).collect();
+        for _ in 0..swaps {
+            let a = rng.range_usize(0..len);
+            let b = rng.range_usize(0..len);
+            arr.swap(a, b);
+        }
+        arr
+    }
+
+    // `runs` ascending runs of random values laid end to end
+    fn sorted_runs(rng: &mut Rng, len: usize, runs: usize) -> Vec<i32> {
+        let mut arr = rng.vec_i32(len, 0..1_000_000);
+        for chunk in arr.chunks_mut(len.div_ceil(runs).max(1)) {
+            chunk.sort();
+        }
+        arr
+    }
+
+    #[test]
+    fn test_min_run_length() {
+        assert_eq!(min_run_length(0), 0);
+        assert_eq!(min_run_length(63), 63);
+        assert_eq!(min_run_length(64), 32);
+        assert_eq!(min_run_length(65), 33);
+        assert_eq!(min_run_length(2048), 32);
+        assert_eq!(min_run_length(2049), 33);
+
+        for len in 64..5000 {
+            let min_run = min_run_length(len);
+            assert!((32..=64).contains(&min_run), "{len} -> {min_run}");
+        }
+    }
+
+    #[test]
+    fn test_gallops_match_partition_point() {
+        let arr: Vec<i32> = (0..100).map(|x| x / 3).collect();
+
+        for key in -1..35 {
+            let pred = |idx: usize| arr[idx] < key;
+            let expected = partition_point(&arr, |x| *x < key);
+
+            assert_eq!(gallop_from_front(arr.len(), pred), expected, "{key}");
+            assert_eq!(gallop_from_back(arr.len(), pred), expected, "{key}");
+        }
+
+        assert_eq!(gallop_from_front(0, |_| true), 0);
+        assert_eq!(gallop_from_back(0, |_| true), 0);
+    }
+
+    #[test]
+    fn test_matches_std() {
+        let mut rng = Rng::new(43);
+
+        for len in (0..200).chain([500, 1000, 4096, 10_000]) {
+            for input in [
+                rng.vec_i32(len, i32::MIN..i32::MAX),
+                rng.vec_i32(len, 0..8),
+                nearly_sorted(&mut rng, len, len / 50),
+                sorted_runs(&mut rng, len, 7),
+                (0..len as i32).rev().collect(),
+            ] {
+                let mut arr = input.clone();
+                let mut expected = input;
+                expected.sort();
+
+                tim_sort(&mut arr);
+                assert_eq!(arr, expected, "{len}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_stable_on_records() {
+        let mut rng = Rng::new(430);
+
+        for len in [10, 63, 64, 65, 500, 5000] {
+            // (key, original position), presorted blocks so the merges gallop
+            let mut input: Vec<(u8, usize)> = (0..len).map(|i| (rng.below(6) as u8, i)).collect();
+            for chunk in input.chunks_mut(97) {
+                chunk.sort_by_key(|record| record.0);
+            }
+
+            let mut arr = input.clone();
+            let mut expected = input;
+            expected.sort_by_key(|record| record.0);
+
+            tim_sort_by(&mut arr, |a, b| a.0.cmp(&b.0));
+            assert_eq!(arr, expected, "{len}");
+        }
+    }
+
+    #[test]
+    fn test_traced_reports_every_compare() {
+        let mut rng = Rng::new(431);
+
+        for input in [rng.vec_i32(40, 0..10), sorted_runs(&mut rng, 5000, 9)] {
+            let mut arr = input.clone();
+            let mut compares = 0;
+            let mut counter = CountingTracer::default();
+            tim_sort_by_traced(
+                &mut arr,
+                |a, b| {
+                    compares += 1;
+                    a.cmp(b)
+                },
+                &mut counter,
+            );
+
+            assert!(is_sorted(&arr));
+            assert_eq!(counter.compares, compares);
+        }
+    }
+
+    #[test]
+    fn test_presorted_input_is_linear() {
+        let len = 10_000;
+
+        let mut sorted: Vec<i32> = (0..len).collect();
+        assert_eq!(count_compares(&mut sorted), len as usize - 1);
+
+        let mut reverse: Vec<i32> = (0..len).rev().collect();
+        assert_eq!(count_compares(&mut reverse), len as usize - 1);
+        assert_eq!(reverse, sorted);
+    }
+
+    #[test]
+    fn test_galloping_merges_blocks_cheaply() {
+        let len = 10_000;
+
+        // two sorted halves, the second entirely smaller than the first
+        let mut swapped: Vec<i32> = (len / 2..len).chain(0..len / 2).collect();
+        assert!(count_compares(&mut swapped) < len as usize + 100);
+        assert!(is_sorted(&swapped));
+
+        // interleaved blocks of 500, merges should skip through whole blocks
+        let mut blocks: Vec<i32> = (0..len).collect();
+        blocks.sort_by_key(|x| (x / 500 % 2, x / 1000, x % 500));
+        let compares = count_compares(&mut blocks);
+        assert!(compares < 2 * len as usize, "{compares}");
+        assert!(is_sorted(&blocks));
+    }
+
+    #[test]
+    fn test_beats_plain_merge_on_nearly_sorted_input() {
+        let mut rng = Rng::new(431);
+        let input = nearly_sorted(&mut rng, 20_000, 20);
+        let n_log_n = 20_000 * 15;
+
+        let compares = count_compares(&mut input.clone());
+        assert!(compares < n_log_n / 4, "{compares}");
+    }
+
+    #[test]
+    fn test_beats_quicksort_on_nearly_sorted_input() {
+        use crate::quicksort::quick_sort_by;
+
+        let mut rng = Rng::new(432);
+        let len = 10_000;
+
+        // a few stray pairs, and a sorted prefix with a random tail appended
+        let mut with_tail: Vec<i32> = (0..len as i32 - 100).collect();
+        with_tail.extend(rng.vec_i32(100, 0..len as i32));
+
+        for input in [nearly_sorted(&mut rng, len, 10), with_tail] {
+            let tim = count_compares(&mut input.clone());
+
+            let mut quick = 0;
+            quick_sort_by(&mut input.clone(), |a, b| {
+                quick += 1;
+                a.cmp(b)
+            });
+
+            assert!(tim * 4 < quick, "timsort {tim} vs quicksort {quick}");
+        }
+    }
+
+    /// run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_partially_sorted_inputs() {
+        use crate::merge_sort::{MergeVariant, merge_sort_by};
+        use crate::quicksort::{intro_sort_by, quick_sort_by};
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        // plain quicksort goes quadratic on these, so the size stays modest
+        let len = 50_000;
+        let mut rng = Rng::new(1);
+
+        let inputs = [
+            ("random", rng.vec_i32(len, 0..i32::MAX)),
+            ("1% swapped", nearly_sorted(&mut rng, len, len / 100)),
+            ("16 runs", sorted_runs(&mut rng, len, 16)),
+            ("sorted + tail", {
+                let mut arr: Vec<i32> = (0..len as i32).collect();
+                let tail = rng.vec_i32(len / 20, 0..len as i32);
+                arr[len - len / 20..].copy_from_slice(&tail);
+                arr
+            }),
+        ];
+
+        type Sort = fn(&mut [i32]);
+        let sorts: [(&str, Sort); 5] = [
+            ("quick_sort", |arr| quick_sort_by(arr, i32::cmp)),
+            ("intro_sort", |arr| intro_sort_by(arr, i32::cmp)),
+            ("merge_sort natural", |arr| {
+                merge_sort_by(arr, MergeVariant::Natural, i32::cmp)
+            }),
+            ("tim_sort", |arr| tim_sort(arr)),
+            ("slice::sort", |arr| arr.sort()),
+        ];
+
+        for (shape, input) in &inputs {
+            println!("{shape}");
+
+            for (name, sort) in sorts {
+                let mut arr = input.clone();
+                let start = Instant::now();
+                sort(black_box(&mut arr));
+                println!("  {name:<20} {:?}", start.elapsed());
+            }
+        }
+    }
+}
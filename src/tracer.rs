@@ -0,0 +1,207 @@
+use std::io::{self, Write};
+
+/// a single step taken by one of the search or sort algorithms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// the element at this index was looked at
+    Probe(usize),
+    /// the elements at these two indices were compared
+    Compare(usize, usize),
+    /// the elements at these two indices were swapped
+    Swap(usize, usize),
+}
+
+impl TraceEvent {
+    /// the same event with every index moved up by `by`, for when an algorithm
+    /// runs on a subslice starting at `by`
+    pub fn shifted(self, by: usize) -> Self {
+        match self {
+            TraceEvent::Probe(idx) => TraceEvent::Probe(idx + by),
+            TraceEvent::Compare(a, b) => TraceEvent::Compare(a + by, b + by),
+            TraceEvent::Swap(a, b) => TraceEvent::Swap(a + by, b + by),
+        }
+    }
+}
+
+/// observer handed to the `_traced` variants of the searches and sorts
+pub trait Tracer {
+    fn trace(&mut self, event: TraceEvent);
+}
+
+/// tracer for the sorts that store values rather than only swap them, which
+/// also sees each value written so the write can be replayed later
+///
+/// writes aren't a `TraceEvent`, so a sort can only report them to a tracer
+/// that knows what to do with the value
+pub trait WriteTracer<T>: Tracer {
+    /// `value` was just stored at `idx`, overwriting what was there
+    fn write(&mut self, idx: usize, value: &T);
+}
+
+/// ignores every event, what the untraced variants use
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopTracer;
+
+impl Tracer for NoopTracer {
+    fn trace(&mut self, _event: TraceEvent) {}
+}
+
+impl<T> WriteTracer<T> for NoopTracer {
+    fn write(&mut self, _idx: usize, _value: &T) {}
+}
+
+/// tallies events by kind
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CountingTracer {
+    pub probes: usize,
+    pub compares: usize,
+    pub swaps: usize,
+    pub writes: usize,
+}
+
+impl Tracer for CountingTracer {
+    fn trace(&mut self, event: TraceEvent) {
+        match event {
+            TraceEvent::Probe(_) => self.probes += 1,
+            TraceEvent::Compare(_, _) => self.compares += 1,
+            TraceEvent::Swap(_, _) => self.swaps += 1,
+        }
+    }
+}
+
+impl<T> WriteTracer<T> for CountingTracer {
+    fn write(&mut self, _idx: usize, _value: &T) {
+        self.writes += 1;
+    }
+}
+
+/// keeps every event in the order it happened
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RecordingTracer {
+    pub events: Vec<TraceEvent>,
+}
+
+impl Tracer for RecordingTracer {
+    fn trace(&mut self, event: TraceEvent) {
+        self.events.push(event);
+    }
+}
+
+/// writes every event on its own line, to stdout by default, handy when
+/// stepping through an algorithm
+///
+/// panics if the output can't be written to, like `println!`
+#[derive(Debug)]
+pub struct PrintTracer<W = io::Stdout>(pub W);
+
+impl Default for PrintTracer {
+    fn default() -> Self {
+        PrintTracer(io::stdout())
+    }
+}
+
+impl<W: Write> Tracer for PrintTracer<W> {
+    fn trace(&mut self, event: TraceEvent) {
+        writeln!(self.0, "{event:?}").expect("failed to print trace event");
+    }
+}
+
+impl<T: std::fmt::Debug, W: Write> WriteTracer<T> for PrintTracer<W> {
+    fn write(&mut self, idx: usize, value: &T) {
+        writeln!(self.0, "Write({idx}, {value:?})").expect("failed to print trace event");
+    }
+}
+
+/// any closure taking an event works as a tracer
+impl<F: FnMut(TraceEvent)> Tracer for F {
+    fn trace(&mut self, event: TraceEvent) {
+        self(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emit(tracer: &mut impl Tracer) {
+        tracer.trace(TraceEvent::Probe(3));
+        tracer.trace(TraceEvent::Compare(0, 1));
+        tracer.trace(TraceEvent::Swap(0, 1));
+        tracer.trace(TraceEvent::Compare(1, 2));
+    }
+
+    #[test]
+    fn test_counting_tracer() {
+        let mut counter = CountingTracer::default();
+        emit(&mut counter);
+
+        assert_eq!(
+            counter,
+            CountingTracer {
+                probes: 1,
+                compares: 2,
+                swaps: 1,
+                writes: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_recording_tracer() {
+        let mut recorder = RecordingTracer::default();
+        emit(&mut recorder);
+
+        assert_eq!(
+            recorder.events,
+            vec![
+                TraceEvent::Probe(3),
+                TraceEvent::Compare(0, 1),
+                TraceEvent::Swap(0, 1),
+                TraceEvent::Compare(1, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_print_tracer() {
+        let mut printer = PrintTracer(Vec::new());
+        emit(&mut printer);
+        printer.write(2, &"x");
+
+        assert_eq!(
+            String::from_utf8(printer.0).unwrap(),
+            "Probe(3)\nCompare(0, 1)\nSwap(0, 1)\nCompare(1, 2)\nWrite(2, \"x\")\n"
+        );
+    }
+
+    #[test]
+    fn test_closure_tracer() {
+        let mut swaps = Vec::new();
+        emit(&mut |event: TraceEvent| {
+            if let TraceEvent::Swap(a, b) = event {
+                swaps.push((a, b));
+            }
+        });
+
+        assert_eq!(swaps, vec![(0, 1)]);
+    }
+
+    #[test]
+    fn test_shifted_events() {
+        assert_eq!(TraceEvent::Probe(1).shifted(10), TraceEvent::Probe(11));
+        assert_eq!(
+            TraceEvent::Compare(0, 3).shifted(5),
+            TraceEvent::Compare(5, 8)
+        );
+        assert_eq!(TraceEvent::Swap(2, 1).shifted(0), TraceEvent::Swap(2, 1));
+    }
+
+    #[test]
+    fn test_counting_writes() {
+        let mut counter = CountingTracer::default();
+        counter.write(0, &"a");
+        counter.write(1, &"b");
+        assert_eq!(counter.writes, 2);
+        assert_eq!(counter.compares + counter.swaps + counter.probes, 0);
+    }
+}
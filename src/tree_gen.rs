@@ -0,0 +1,202 @@
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use crate::binary_node::BinaryNode;
+use crate::rng::Rng;
+
+/// which way a degenerate chain leans
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Chain {
+    /// every node only has a left child (descending values)
+    Left,
+    /// every node only has a right child (ascending values)
+    Right,
+    /// alternates right and left children while staying a valid BST
+    ZigZag,
+}
+
+/// random BST of `len` distinct values, built by inserting them in random order
+pub fn random_bst(rng: &mut Rng, len: usize) -> Option<Box<BinaryNode>> {
+    let spread = (len as i32).saturating_mul(10).max(1);
+    let mut values = BTreeSet::new();
+
+    while values.len() < len {
+        values.insert(rng.range_i32(-spread..spread));
+    }
+
+    let mut values: Vec<i32> = values.into_iter().collect();
+    rng.shuffle(&mut values);
+
+    let mut iter = values.into_iter();
+    let mut root = BinaryNode::new(iter.next()?);
+
+    for value in iter {
+        root.insert(value);
+    }
+
+    Some(Box::new(root))
+}
+
+/// random tree of `len` nodes with an arbitrary shape
+/// values are drawn from `values` and do not respect BST ordering
+pub fn random_tree(rng: &mut Rng, len: usize, values: Range<i32>) -> Option<Box<BinaryNode>> {
+    if len == 0 {
+        return None;
+    }
+
+    let value = rng.range_i32(values.clone());
+    let left_len = rng.range_usize(0..len);
+    let left = random_tree(rng, left_len, values.clone());
+    let right = random_tree(rng, len - left_len - 1, values);
+
+    Some(Box::new(BinaryNode::with_children(value, left, right)))
+}
+
+/// complete tree of `len` nodes holding `1..=len` in level order
+pub fn complete_tree(len: usize) -> Option<Box<BinaryNode>> {
+    fn build(idx: usize, len: usize) -> Option<Box<BinaryNode>> {
+        if idx >= len {
+            return None;
+        }
+
+        Some(Box::new(BinaryNode::with_children(
+            idx as i32 + 1,
+            build(2 * idx + 1, len),
+            build(2 * idx + 2, len),
+        )))
+    }
+
+    build(0, len)
+}
+
+/// worst-case BST of `len` nodes where every node has at most one child
+pub fn degenerate_chain(len: usize, chain: Chain) -> Option<Box<BinaryNode>> {
+    let values: Vec<i32> = match chain {
+        Chain::Left => (0..len as i32).rev().collect(),
+        Chain::Right => (0..len as i32).collect(),
+        Chain::ZigZag => {
+            let (mut lo, mut hi) = (0, len as i32 - 1);
+            let mut values = Vec::with_capacity(len);
+
+            while lo <= hi {
+                values.push(lo);
+                lo += 1;
+
+                if lo <= hi {
+                    values.push(hi);
+                    hi -= 1;
+                }
+            }
+
+            values
+        }
+    };
+
+    // build bottom-up so long chains don't need deep recursion
+    let mut child: Option<Box<BinaryNode>> = None;
+
+    for &value in values.iter().rev() {
+        let mut node = BinaryNode::new(value);
+
+        if let Some(below) = child.take() {
+            if below.value < value {
+                node.left = Some(below);
+            } else {
+                node.right = Some(below);
+            }
+        }
+
+        child = Some(Box::new(node));
+    }
+
+    child
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bt_in_order::in_order_search;
+
+    fn count(node: Option<&BinaryNode>) -> usize {
+        node.map_or(0, |n| {
+            1 + count(n.left.as_deref()) + count(n.right.as_deref())
+        })
+    }
+
+    #[test]
+    fn test_random_bst_is_sorted_in_order() {
+        let mut rng = Rng::new(1);
+
+        for len in 0..50 {
+            let tree = random_bst(&mut rng, len);
+            let values = in_order_search(tree.as_deref());
+
+            assert_eq!(values.len(), len);
+            assert!(values.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+
+    #[test]
+    fn test_random_tree_has_requested_size() {
+        let mut rng = Rng::new(2);
+
+        for len in 0..50 {
+            let tree = random_tree(&mut rng, len, 0..10);
+            assert_eq!(count(tree.as_deref()), len);
+        }
+    }
+
+    #[test]
+    fn test_generators_are_deterministic() {
+        let a = random_tree(&mut Rng::new(9), 30, -5..5);
+        let b = random_tree(&mut Rng::new(9), 30, -5..5);
+        assert_eq!(a, b);
+
+        let a = random_bst(&mut Rng::new(9), 30);
+        let b = random_bst(&mut Rng::new(9), 30);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_complete_tree() {
+        //       1
+        //      / \
+        //     2   3
+        //    /
+        //   4
+        let expected = BinaryNode::with_children(
+            1,
+            Some(Box::new(BinaryNode::with_children(
+                2,
+                Some(Box::new(BinaryNode::new(4))),
+                None,
+            ))),
+            Some(Box::new(BinaryNode::new(3))),
+        );
+
+        assert_eq!(complete_tree(4).as_deref(), Some(&expected));
+        assert_eq!(complete_tree(0), None);
+        assert_eq!(BinaryNode::height(complete_tree(100).as_deref()), 7);
+    }
+
+    #[test]
+    fn test_degenerate_chains() {
+        for chain in [Chain::Left, Chain::Right, Chain::ZigZag] {
+            let tree = degenerate_chain(100, chain);
+
+            assert_eq!(BinaryNode::height(tree.as_deref()), 100);
+            assert_eq!(
+                in_order_search(tree.as_deref()),
+                (0..100).collect::<Vec<_>>()
+            );
+        }
+
+        let zigzag = degenerate_chain(3, Chain::ZigZag).unwrap();
+        assert_eq!(zigzag.value, 0);
+        assert_eq!(zigzag.right.as_ref().unwrap().value, 2);
+        assert_eq!(
+            zigzag.right.as_ref().unwrap().left.as_ref().unwrap().value,
+            1
+        );
+    }
+}
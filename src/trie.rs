@@ -0,0 +1,310 @@
+#[derive(Debug, Default)]
+struct TrieNode {
+    is_word: bool,
+    // kept sorted by char, so lookups can binary search
+    // and walking the children yields words in lexicographic order
+    children: Vec<(char, TrieNode)>,
+}
+
+impl TrieNode {
+    fn child(&self, ch: char) -> Option<&TrieNode> {
+        self.children
+            .binary_search_by_key(&ch, |(c, _)| *c)
+            .ok()
+            .map(|idx| &self.children[idx].1)
+    }
+
+    fn child_or_insert(&mut self, ch: char) -> &mut TrieNode {
+        let idx = match self.children.binary_search_by_key(&ch, |(c, _)| *c) {
+            Ok(idx) => idx,
+            Err(idx) => {
+                self.children.insert(idx, (ch, TrieNode::default()));
+                idx
+            }
+        };
+
+        &mut self.children[idx].1
+    }
+}
+
+/// prefix tree over arbitrary unicode strings
+#[derive(Debug, Default)]
+pub struct Trie {
+    length: usize,
+    root: TrieNode,
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// inserts a word, returns true if it was not already present
+    pub fn insert(&mut self, word: &str) -> bool {
+        let mut node = &mut self.root;
+
+        for ch in word.chars() {
+            node = node.child_or_insert(ch);
+        }
+
+        if node.is_word {
+            return false;
+        }
+
+        node.is_word = true;
+        self.length += 1;
+        true
+    }
+
+    fn find(&self, prefix: &str) -> Option<&TrieNode> {
+        let mut node = &self.root;
+
+        for ch in prefix.chars() {
+            node = node.child(ch)?;
+        }
+
+        Some(node)
+    }
+
+    /// exact lookup of a whole word
+    pub fn contains(&self, word: &str) -> bool {
+        self.find(word).is_some_and(|node| node.is_word)
+    }
+
+    /// true if any stored word starts with `prefix`
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.find(prefix)
+            .is_some_and(|node| node.is_word || !node.children.is_empty())
+    }
+
+    /// deletes a word and prunes the branch nodes it no longer needs
+    /// returns true if the word was found and deleted
+    pub fn delete(&mut self, word: &str) -> bool {
+        let chars: Vec<char> = word.chars().collect();
+        let deleted = Self::delete_from(&mut self.root, &chars);
+
+        if deleted {
+            self.length -= 1;
+        }
+
+        deleted
+    }
+
+    fn delete_from(node: &mut TrieNode, word: &[char]) -> bool {
+        let Some((&ch, rest)) = word.split_first() else {
+            let was_word = node.is_word;
+            node.is_word = false;
+            return was_word;
+        };
+
+        let Ok(idx) = node.children.binary_search_by_key(&ch, |(c, _)| *c) else {
+            return false;
+        };
+
+        let child = &mut node.children[idx].1;
+        let deleted = Self::delete_from(child, rest);
+
+        // drop the child once nothing passes through it anymore
+        if deleted && !child.is_word && child.children.is_empty() {
+            node.children.remove(idx);
+        }
+
+        deleted
+    }
+
+    /// every stored word starting with `prefix`, in lexicographic order
+    pub fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        let mut words = Vec::new();
+
+        if let Some(node) = self.find(prefix) {
+            let mut path = prefix.to_string();
+            Self::collect(node, &mut path, &mut words);
+        }
+
+        words
+    }
+
+    fn collect(node: &TrieNode, path: &mut String, words: &mut Vec<String>) {
+        // pre
+        if node.is_word {
+            words.push(path.clone());
+        }
+
+        // recurse
+        for (ch, child) in &node.children {
+            path.push(*ch);
+            Self::collect(child, path, words);
+            // post
+            path.pop();
+        }
+    }
+
+    /// longest prefix shared by every stored word
+    pub fn longest_common_prefix(&self) -> String {
+        let mut prefix = String::new();
+        let mut node = &self.root;
+
+        while !node.is_word && node.children.len() == 1 {
+            let (ch, child) = &node.children[0];
+            prefix.push(*ch);
+            node = child;
+        }
+
+        prefix
+    }
+
+    #[cfg(test)]
+    /// counts the nodes in the trie, used to check cleanup after deletes
+    fn node_count(&self) -> usize {
+        fn count(node: &TrieNode) -> usize {
+            1 + node.children.iter().map(|(_, c)| count(c)).sum::<usize>()
+        }
+
+        count(&self.root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Trie;
+
+    fn sample_trie() -> Trie {
+        let mut trie = Trie::new();
+
+        for word in ["car", "cart", "care", "cat", "dog", "do"] {
+            trie.insert(word);
+        }
+
+        trie
+    }
+
+    #[test]
+    fn test_insert_and_contains() {
+        let mut trie = sample_trie();
+
+        assert_eq!(trie.len(), 6);
+        assert!(trie.contains("car"));
+        assert!(trie.contains("do"));
+        assert!(!trie.contains("ca"));
+        assert!(!trie.contains("cars"));
+        assert!(!trie.contains(""));
+
+        assert!(!trie.insert("car"), "duplicate insert should return false");
+        assert_eq!(trie.len(), 6);
+    }
+
+    #[test]
+    fn test_starts_with() {
+        let trie = sample_trie();
+
+        assert!(trie.starts_with(""));
+        assert!(trie.starts_with("ca"));
+        assert!(trie.starts_with("cart"));
+        assert!(!trie.starts_with("cb"));
+        assert!(!trie.starts_with("carts"));
+        assert!(!Trie::new().starts_with(""));
+    }
+
+    #[test]
+    fn test_words_with_prefix_in_lexicographic_order() {
+        let trie = sample_trie();
+
+        let mut expected = trie.words_with_prefix("");
+        expected.sort();
+        assert_eq!(trie.words_with_prefix(""), expected);
+
+        assert_eq!(trie.words_with_prefix("car"), vec!["car", "care", "cart"]);
+        assert_eq!(
+            trie.words_with_prefix(""),
+            vec!["car", "care", "cart", "cat", "do", "dog"]
+        );
+        assert!(trie.words_with_prefix("x").is_empty());
+    }
+
+    #[test]
+    fn test_delete_with_cleanup() {
+        let mut trie = sample_trie();
+        let before = trie.node_count();
+
+        assert!(trie.delete("cart"));
+        assert!(!trie.contains("cart"));
+        assert!(trie.contains("car"));
+        assert_eq!(trie.node_count(), before - 1);
+
+        // "car" is a prefix of "care", so no nodes can go
+        assert!(trie.delete("car"));
+        assert!(trie.contains("care"));
+        assert_eq!(trie.node_count(), before - 1);
+
+        assert!(!trie.delete("car"));
+        assert!(!trie.delete("ca"));
+        assert!(!trie.delete("zebra"));
+
+        for word in ["care", "cat", "dog", "do"] {
+            assert!(trie.delete(word));
+        }
+
+        assert!(trie.is_empty());
+        assert_eq!(trie.node_count(), 1);
+    }
+
+    #[test]
+    fn test_longest_common_prefix() {
+        let mut trie = Trie::new();
+        assert_eq!(trie.longest_common_prefix(), "");
+
+        trie.insert("interview");
+        assert_eq!(trie.longest_common_prefix(), "interview");
+
+        trie.insert("internet");
+        trie.insert("interval");
+        assert_eq!(trie.longest_common_prefix(), "inter");
+
+        // a stored word stops the prefix from growing past it
+        trie.insert("in");
+        assert_eq!(trie.longest_common_prefix(), "in");
+
+        trie.insert("out");
+        assert_eq!(trie.longest_common_prefix(), "");
+    }
+
+    #[test]
+    fn test_unicode_words() {
+        let mut trie = Trie::new();
+
+        for word in ["日本", "日本語", "日曜日", "über", "übel", "🦀rust"] {
+            trie.insert(word);
+        }
+
+        assert!(trie.contains("日本語"));
+        assert!(trie.starts_with("日"));
+        assert!(trie.starts_with("🦀"));
+        assert_eq!(
+            trie.words_with_prefix("日"),
+            vec!["日曜日", "日本", "日本語"]
+        );
+        assert_eq!(trie.words_with_prefix("üb"), vec!["übel", "über"]);
+
+        assert!(trie.delete("日本"));
+        assert_eq!(trie.words_with_prefix("日本"), vec!["日本語"]);
+    }
+
+    #[test]
+    fn test_empty_word() {
+        let mut trie = Trie::new();
+
+        assert!(trie.insert(""));
+        assert!(trie.contains(""));
+        assert_eq!(trie.words_with_prefix(""), vec![""]);
+        assert!(trie.delete(""));
+        assert!(trie.is_empty());
+    }
+}
@@ -1,56 +1,178 @@
+use crate::tracer::{NoopTracer, TraceEvent, Tracer};
+
 fn two_crystal_balls(breaks: &[bool]) -> i32 {
-    let jump_amount = (breaks.len() as f64).sqrt().floor() as usize;
+    two_crystal_balls_traced(breaks, &mut NoopTracer)
+}
 
-    println!("jumping amount: {}", jump_amount);
+/// same as `two_crystal_balls`, reporting every floor it checks to the tracer
+fn two_crystal_balls_traced(breaks: &[bool], tracer: &mut impl Tracer) -> i32 {
+    let jump_amount = (breaks.len() as f64).sqrt().floor() as usize;
 
     let mut starting_point = jump_amount;
 
-    println!("starting point: {}", starting_point);
-
     // first ball validation of breaks
     while starting_point < breaks.len() {
+        tracer.trace(TraceEvent::Probe(starting_point));
+
         if breaks[starting_point] {
-            println!("found start point of true: {}", starting_point);
             break;
         }
 
-        println!("starting point is false: {}", starting_point);
-
         starting_point += jump_amount;
-
-        println!("increased amount of start point: {}", starting_point);
     }
 
     starting_point -= jump_amount;
 
-    println!("decrease starting point: {}", starting_point);
-
     let mut j = 0;
 
     // second ball validation of breaks
     while j <= jump_amount && starting_point < breaks.len() {
+        tracer.trace(TraceEvent::Probe(starting_point));
+
         if breaks[starting_point] {
-            println!("return the value: {}", starting_point);
             return starting_point as i32;
         }
 
-        println!("we haven't found the value yet: {}", starting_point);
-
         j += 1;
         starting_point += 1;
-
-        println!(
-            "increase the value of last truth point: {} and start point: {}",
-            j, starting_point
-        );
     }
 
     -1
 }
 
+/// result of running a drop strategy against a break array
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DropOutcome {
+    /// lowest floor where a ball breaks, `None` if it never does
+    pub floor: Option<usize>,
+    pub drops: usize,
+}
+
+/// dp over drops: with `d` drops and `k` balls we can settle
+/// `floors(d, k) = floors(d - 1, k - 1) + floors(d - 1, k) + 1` floors,
+/// one floor for the drop itself plus what is left below if it breaks
+/// and above if it doesn't
+///
+/// `rows[d][k]` is `floors(d, k)`, filled until the last row covers every floor
+struct DropsTable {
+    rows: Vec<Vec<usize>>,
+}
+
+impl DropsTable {
+    fn new(balls: usize, floors: usize) -> Self {
+        assert!(balls > 0 || floors == 0, "need at least one ball");
+
+        // one ball per bit of `floors` already allows a plain binary search,
+        // more than that never saves a drop
+        let balls = balls.min((usize::BITS - floors.leading_zeros()) as usize);
+        let mut rows = vec![vec![0usize; balls + 1]];
+
+        while rows[rows.len() - 1][balls] < floors {
+            let prev = &rows[rows.len() - 1];
+            let mut row = vec![0usize; balls + 1];
+
+            for k in 1..=balls {
+                row[k] = prev[k].saturating_add(prev[k - 1]).saturating_add(1);
+            }
+
+            rows.push(row);
+        }
+
+        DropsTable { rows }
+    }
+
+    // the column standing in for `balls` balls
+    fn column(&self, balls: usize) -> usize {
+        balls.min(self.rows[0].len() - 1)
+    }
+
+    fn min_drops(&self, balls: usize, floors: usize) -> usize {
+        let k = self.column(balls);
+        self.rows.iter().position(|row| row[k] >= floors).unwrap()
+    }
+
+    // floor to drop from when the breaking floor is in `low..high`, which
+    // must be no more floors than the table was built for
+    fn next_drop(&self, balls: usize, low: usize, high: usize) -> usize {
+        assert!(low < high, "nothing left to search");
+
+        let k = self.column(balls);
+        let drops = self.min_drops(k, high - low);
+
+        // if it breaks we must be able to finish below with one ball and one drop less
+        (low + self.rows[drops - 1][k - 1]).min(high - 1)
+    }
+}
+
+/// optimal worst-case number of drops to find the breaking floor
+pub fn min_drops(balls: usize, floors: usize) -> usize {
+    DropsTable::new(balls, floors).min_drops(balls, floors)
+}
+
+/// floor to drop from next when the breaking floor is known to be in `low..high`
+/// (`high` itself standing for "never breaks")
+pub fn next_drop(balls: usize, low: usize, high: usize) -> usize {
+    assert!(low < high, "nothing left to search");
+
+    DropsTable::new(balls, high - low).next_drop(balls, low, high)
+}
+
+/// floors the first ball is dropped from, in order, as long as it keeps surviving
+pub fn drop_strategy(balls: usize, floors: usize) -> Vec<usize> {
+    let table = DropsTable::new(balls, floors);
+    let mut low = 0;
+    let mut plan = Vec::new();
+
+    while low < floors {
+        let floor = table.next_drop(balls, low, floors);
+        plan.push(floor);
+        low = floor + 1;
+    }
+
+    plan
+}
+
+/// runs the optimal strategy for `balls` balls against `breaks`
+pub fn k_crystal_balls(balls: usize, breaks: &[bool]) -> DropOutcome {
+    k_crystal_balls_traced(balls, breaks, &mut NoopTracer)
+}
+
+/// same as `k_crystal_balls`, reporting every drop to the tracer
+pub fn k_crystal_balls_traced(
+    balls: usize,
+    breaks: &[bool],
+    tracer: &mut impl Tracer,
+) -> DropOutcome {
+    let table = DropsTable::new(balls, breaks.len());
+    let mut balls_left = balls;
+    let mut low = 0;
+    let mut high = breaks.len();
+    let mut drops = 0;
+
+    while low < high {
+        let floor = table.next_drop(balls_left, low, high);
+        drops += 1;
+        tracer.trace(TraceEvent::Probe(floor));
+
+        if breaks[floor] {
+            balls_left -= 1;
+            high = floor;
+        } else {
+            low = floor + 1;
+        }
+    }
+
+    DropOutcome {
+        floor: (low < breaks.len()).then_some(low),
+        drops,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::two_crystal_balls;
+    use super::*;
+    use crate::rng::Rng;
+    use crate::tracer::{CountingTracer, RecordingTracer};
 
     #[test]
     fn tests_breaks_at_floor_5() {
@@ -84,4 +206,131 @@ mod tests {
 
         assert_eq!(result, 9);
     }
+
+    #[test]
+    fn it_computes_optimal_drop_counts() {
+        assert_eq!(min_drops(2, 100), 14);
+        assert_eq!(min_drops(3, 100), 9);
+        assert_eq!(min_drops(2, 36), 8);
+        assert_eq!(min_drops(1, 10), 10);
+        assert_eq!(min_drops(4, 0), 0);
+        assert_eq!(min_drops(20, 1_000_000), 20);
+    }
+
+    #[test]
+    fn it_plans_the_classic_two_ball_strategy() {
+        // 14, 27, 39, ... in 1-based floors for the 100 floor puzzle
+        let plan = drop_strategy(2, 100);
+        assert_eq!(plan[..4], [13, 26, 38, 49]);
+        assert_eq!(*plan.last().unwrap(), 99);
+
+        // a single ball has to go floor by floor
+        assert_eq!(drop_strategy(1, 4), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn it_agrees_with_two_crystal_balls() {
+        let cases: [&[bool]; 4] = [
+            &[
+                false, false, false, false, false, true, true, true, true, true,
+            ],
+            &[false, false, false, false, false, false, false, false],
+            &[true, true, true, true, true],
+            &[
+                false, false, false, false, false, false, false, false, false, true,
+            ],
+        ];
+
+        for breaks in cases {
+            let outcome = k_crystal_balls(2, breaks);
+            let expected = two_crystal_balls(breaks);
+
+            assert_eq!(outcome.floor.map_or(-1, |f| f as i32), expected);
+            assert!(outcome.drops <= min_drops(2, breaks.len()));
+        }
+    }
+
+    #[test]
+    fn it_never_exceeds_the_optimal_worst_case() {
+        for balls in 1..=4 {
+            for floors in 0..60 {
+                let mut worst = 0;
+
+                // every possible breaking floor, including never
+                for threshold in 0..=floors {
+                    let breaks: Vec<bool> = (0..floors).map(|f| f >= threshold).collect();
+                    let outcome = k_crystal_balls(balls, &breaks);
+
+                    assert_eq!(outcome.floor, (threshold < floors).then_some(threshold));
+                    worst = worst.max(outcome.drops);
+                }
+
+                assert_eq!(
+                    worst,
+                    min_drops(balls, floors),
+                    "{balls} balls, {floors} floors"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn it_handles_more_balls_than_floors() {
+        assert_eq!(min_drops(usize::MAX, 100), 7);
+        assert_eq!(min_drops(1_000_000_000, 0), 0);
+        assert_eq!(next_drop(usize::MAX / 16, 0, 2), 1);
+
+        let outcome = k_crystal_balls(usize::MAX / 16, &[false, true]);
+        assert_eq!(outcome.floor, Some(1));
+        assert_eq!(outcome.drops, 2);
+
+        // past one ball per bit extra balls change nothing
+        let breaks: Vec<bool> = (0..1000).map(|f| f >= 614).collect();
+        assert_eq!(
+            k_crystal_balls(10, &breaks),
+            k_crystal_balls(1_000_000_000, &breaks)
+        );
+    }
+
+    #[test]
+    fn it_finds_random_thresholds() {
+        let mut rng = Rng::new(35);
+
+        for _ in 0..200 {
+            let floors = rng.range_usize(1..500);
+            let threshold = rng.range_usize(0..floors + 1);
+            let balls = rng.range_usize(1..6);
+            let breaks: Vec<bool> = (0..floors).map(|f| f >= threshold).collect();
+
+            let outcome = k_crystal_balls(balls, &breaks);
+
+            assert_eq!(outcome.floor, (threshold < floors).then_some(threshold));
+            assert!(outcome.drops <= min_drops(balls, floors));
+        }
+    }
+
+    #[test]
+    fn it_traces_each_checked_floor() {
+        let breaks = [
+            false, false, false, false, false, true, true, true, true, true,
+        ];
+        let mut recorder = RecordingTracer::default();
+
+        assert_eq!(two_crystal_balls_traced(&breaks, &mut recorder), 5);
+
+        // jumps of 3 hit 3 then break at 6, then walks up from 3
+        let probes: Vec<TraceEvent> = [3, 6, 3, 4, 5].map(TraceEvent::Probe).to_vec();
+        assert_eq!(recorder.events, probes);
+    }
+
+    #[test]
+    fn it_counts_drops_through_the_tracer() {
+        let breaks: Vec<bool> = (0..100).map(|f| f >= 73).collect();
+        let mut counter = CountingTracer::default();
+
+        let outcome = k_crystal_balls_traced(2, &breaks, &mut counter);
+
+        assert_eq!(outcome.floor, Some(73));
+        assert_eq!(counter.probes, outcome.drops);
+    }
 }